@@ -0,0 +1,135 @@
+// Compares the bitset behind `Words` against the `BTreeSet` it
+// replaced and the `HashSet` the `Set` alias could have been. Each
+// implementation narrows the full vocabulary the way `process_hints`
+// does: intersect with the words having a letter in a position, then
+// remove the words having another.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::collections::{BTreeSet, HashSet};
+use wordlebot::dictionary::{WordLists, Words};
+use wordlebot::{feedback, process_hints};
+
+// The operations `Words` provides. The set versions rebuild the set
+// each time, like `Words` used to.
+
+trait Ops: Clone {
+    fn preserve(&mut self, words: &Self);
+    fn remove(&mut self, words: &Self);
+    fn union(&mut self, words: &Self);
+}
+
+impl Ops for BTreeSet<&'static str> {
+    fn preserve(&mut self, words: &Self) {
+        *self = self.intersection(words).cloned().collect()
+    }
+
+    fn remove(&mut self, words: &Self) {
+        *self = self.difference(words).cloned().collect()
+    }
+
+    fn union(&mut self, words: &Self) {
+        *self = BTreeSet::union(self, words).cloned().collect()
+    }
+}
+
+impl Ops for HashSet<&'static str> {
+    fn preserve(&mut self, words: &Self) {
+        *self = self.intersection(words).cloned().collect()
+    }
+
+    fn remove(&mut self, words: &Self) {
+        *self = self.difference(words).cloned().collect()
+    }
+
+    fn union(&mut self, words: &Self) {
+        *self = HashSet::union(self, words).cloned().collect()
+    }
+}
+
+impl Ops for Words {
+    fn preserve(&mut self, words: &Self) {
+        Words::preserve(self, words)
+    }
+
+    fn remove(&mut self, words: &Self) {
+        Words::remove(self, words)
+    }
+
+    fn union(&mut self, words: &Self) {
+        Words::union(self, words)
+    }
+}
+
+// Returns the sets the benchmarks use, from the built-in lists: the
+// vocabulary, the answers with an E at the end, the ones with an R
+// in the middle and the ones starting with S.
+
+fn sets() -> [Words; 4] {
+    let lists = WordLists::new();
+    let gt = lists.green_table();
+
+    [
+        lists.vocabulary(),
+        gt.get(&(4, 'e')).unwrap().clone(),
+        gt.get(&(2, 'r')).unwrap().clone(),
+        gt.get(&(0, 's')).unwrap().clone(),
+    ]
+}
+
+fn narrow<T: Ops>([vocab, e, r, s]: &[T; 4]) -> T {
+    let mut vocab = vocab.clone();
+    let mut keep = e.clone();
+
+    keep.union(s);
+    vocab.preserve(&keep);
+    vocab.remove(r);
+    vocab
+}
+
+fn bench_sets(c: &mut Criterion) {
+    let bitsets = sets();
+    let btree = bitsets.clone().map(|e| e.iter().collect::<BTreeSet<_>>());
+    let hash = bitsets.clone().map(|e| e.iter().collect::<HashSet<_>>());
+    let mut group = c.benchmark_group("narrow");
+
+    group.bench_function("bitset", |b| b.iter(|| narrow(black_box(&bitsets))));
+    group.bench_function("btree", |b| b.iter(|| narrow(black_box(&btree))));
+    group.bench_function("hash", |b| b.iter(|| narrow(black_box(&hash))));
+    group.finish();
+}
+
+// Applies the hints of a typical first guess to the full vocabulary.
+
+fn bench_hints(c: &mut Criterion) {
+    let lists = WordLists::new();
+    let vocab = lists.vocabulary();
+    let gt = lists.green_table();
+    let ft = lists.freq_table();
+    let hints = feedback::score("crane", "otter");
+
+    c.bench_function("process_hints", |b| {
+        b.iter(|| {
+            process_hints(vocab.clone(), &gt, &ft, black_box("crane"), &hints)
+        })
+    });
+}
+
+// Iterating is what the strategies mostly do with a set.
+
+fn bench_iter(c: &mut Criterion) {
+    let lists = WordLists::new();
+    let bitset = lists.guesses();
+    let btree: BTreeSet<_> = bitset.iter().collect();
+    let mut group = c.benchmark_group("iter");
+
+    group.bench_function("bitset", |b| {
+        b.iter(|| black_box(&bitset).iter().count())
+    });
+    group.bench_function("btree", |b| {
+        b.iter(|| black_box(&btree).iter().count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_sets, bench_hints, bench_iter);
+criterion_main!(benches);
@@ -0,0 +1,242 @@
+use crate::dictionary::{CharFreqTable, GreenTable, Words};
+use crate::matrix::{self, Matrix};
+use crate::strategy::{self, Strategy, Turn};
+use crate::Hint;
+use rand::RngCore;
+use std::io;
+
+// The words a game is replayed with: the words that can be the
+// answer, the allowed guesses and the tables used to apply hints.
+// The pattern matrix is used if there is one.
+
+pub struct Tables<'a> {
+    pub vocab: &'a Words,
+    pub guesses: &'a Words,
+    pub gt: &'a GreenTable,
+    pub ft: &'a CharFreqTable,
+    pub matrix: Option<&'a Matrix>,
+}
+
+// How one turn of a game went. Information is measured in bits: each
+// bit halves the number of words that could be the answer.
+
+#[derive(Debug)]
+pub struct TurnReport {
+    pub guess: String,
+
+    // The number of words that could be the answer before and after
+    // the hints.
+    pub before: usize,
+    pub after: usize,
+
+    // The information the hints actually gave and the information
+    // the guess was expected to give, averaged over the words that
+    // could be the answer.
+    pub gained: f64,
+    pub expected: f64,
+
+    // The guess expected to give the most information, and how much.
+    pub best: (&'static str, f64),
+
+    // The guess the strategy would have made.
+    pub bot: &'static str,
+}
+
+impl TurnReport {
+    // How good the guess was, from 0 to 100, compared to the best
+    // guess. It only depends on the choice of word.
+
+    pub fn skill(&self) -> f64 {
+        if self.best.1 > 0.0 {
+            100.0 * self.expected / self.best.1
+        } else {
+            100.0
+        }
+    }
+
+    // How much more (or less) the hints told than was expected. It
+    // only depends on the answer.
+
+    pub fn luck(&self) -> f64 {
+        self.gained - self.expected
+    }
+}
+
+// Replays a game and reports on each turn. `strategy` suggests what
+// wordlebot would have played instead. In hard mode, the best guess
+// and the strategy's guess are only chosen from the guesses hard
+// mode allows.
+
+pub fn analyze(
+    history: &[(String, Vec<Hint>)], tables: &Tables,
+    mut strategy: Box<dyn Strategy>, rng: &mut dyn RngCore, hard: bool,
+) -> io::Result<Vec<TurnReport>> {
+    if hard {
+        strategy = Box::new(strategy::Hard::new(strategy, false))
+    }
+
+    let guesses = tables.guesses;
+    let mut vocab = tables.vocab.clone();
+    let mut reports = Vec::with_capacity(history.len());
+
+    for (idx, (guess, hints)) in history.iter().enumerate() {
+        let turn = Turn {
+            candidates: &vocab,
+            guesses,
+            history: &history[..idx],
+            matrix: tables.matrix,
+        };
+        let legal = if hard {
+            strategy::legal_guesses(&turn)
+        } else {
+            guesses.clone()
+        };
+        let best = strategy::rank(
+            &Turn {
+                guesses: &legal,
+                ..turn
+            },
+            1,
+        )
+        .first()
+        .copied()
+        .unwrap_or_default();
+        let bot = strategy.pick(&turn, rng);
+        let before = vocab.total();
+        let expected = strategy::entropy(guess, &vocab, tables.matrix);
+
+        // A solved turn leaves one word, the answer.
+
+        vocab = if hints.iter().all(|e| *e == Hint::Green) {
+            let pos = vocab.position(guess);
+
+            vocab.retain(|e| Some(e) == pos);
+            vocab
+        } else {
+            matrix::process_hints(
+                tables.matrix,
+                vocab,
+                tables.gt,
+                tables.ft,
+                guess,
+                hints,
+            )
+        };
+
+        if vocab.total() == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("no word fits the hints after guess {}", idx + 1),
+            ));
+        }
+
+        reports.push(TurnReport {
+            guess: guess.clone(),
+            before,
+            after: vocab.total(),
+            gained: (before as f64 / vocab.total() as f64).log2(),
+            expected,
+            best,
+            bot,
+        });
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_analyze() {
+        let words = ["aaaaa", "bbbbb", "ccccc", "ddddd"];
+        let vocab = Words::new(&words);
+        let guesses =
+            Words::new(&["aaaaa", "bbbbb", "ccccc", "ddddd", "abcdz", "zzzzz"]);
+        let gt = GreenTable::new_from_slice(&words);
+        let ft = CharFreqTable::new_from_slice(&words);
+        let history = vec![
+            ("zzzzz".to_string(), feedback::score("zzzzz", "ccccc")),
+            ("abcdz".to_string(), feedback::score("abcdz", "ccccc")),
+            ("ccccc".to_string(), feedback::score("ccccc", "ccccc")),
+        ];
+        let tables = Tables {
+            vocab: &vocab,
+            guesses: &guesses,
+            gt: &gt,
+            ft: &ft,
+            matrix: None,
+        };
+        let reports = analyze(
+            &history,
+            &tables,
+            strategy::build("entropy").unwrap(),
+            &mut StdRng::seed_from_u64(0),
+            false,
+        )
+        .unwrap();
+
+        // The first guess can't tell the words apart.
+
+        assert_eq!((reports[0].before, reports[0].after), (4, 4));
+        assert_eq!(reports[0].skill(), 0.0);
+        assert_eq!(reports[0].best, ("abcdz", 2.0));
+        assert_eq!(reports[0].bot, "abcdz");
+
+        // The second is the best possible guess and gets the
+        // expected information.
+
+        assert_eq!((reports[1].before, reports[1].after), (4, 1));
+        assert_eq!(reports[1].skill(), 100.0);
+        assert_eq!(reports[1].luck(), 0.0);
+
+        assert_eq!((reports[2].before, reports[2].after), (1, 1));
+    }
+
+    // In hard mode, the best guess and the strategy's guess have to
+    // reuse the clues.
+
+    #[test]
+    fn test_analyze_hard() {
+        let words = ["aaaab", "aaaac", "aaaad"];
+        let vocab = Words::new(&words);
+        let guesses =
+            Words::new(&["aaaab", "aaaac", "aaaad", "aaaaz", "bcdzz"]);
+        let gt = GreenTable::new_from_slice(&words);
+        let ft = CharFreqTable::new_from_slice(&words);
+        let history = vec![
+            ("aaaaz".to_string(), feedback::score("aaaaz", "aaaac")),
+            ("aaaac".to_string(), feedback::score("aaaac", "aaaac")),
+        ];
+        let tables = Tables {
+            vocab: &vocab,
+            guesses: &guesses,
+            gt: &gt,
+            ft: &ft,
+            matrix: None,
+        };
+        let analyze = |hard| {
+            analyze(
+                &history,
+                &tables,
+                strategy::build("entropy").unwrap(),
+                &mut StdRng::seed_from_u64(0),
+                hard,
+            )
+            .unwrap()
+        };
+
+        // BCDZZ tells the candidates apart, but it drops the greens.
+
+        let reports = analyze(false);
+
+        assert_eq!((reports[1].best.0, reports[1].bot), ("bcdzz", "bcdzz"));
+
+        let reports = analyze(true);
+
+        assert!(words.contains(&reports[1].best.0));
+        assert!(words.contains(&reports[1].bot));
+    }
+}
@@ -0,0 +1,166 @@
+use super::input;
+use rand::RngCore;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use wordlebot::analysis::{analyze, Tables, TurnReport};
+use wordlebot::dictionary;
+use wordlebot::matrix::Matrix;
+use wordlebot::strategy::Strategy;
+use wordlebot::Hint;
+
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    #[clap(
+        value_name = "FILE",
+        help = "The game to analyze",
+        long_help = "A file holding the game to analyze. Each line has a word that was played followed by its hints, e.g. \"crane bygbb\". Blank lines and lines starting with # are ignored. If not given, the game is read from stdin."
+    )]
+    file: Option<PathBuf>,
+}
+
+// Prints the report of a game as a table followed by a summary.
+
+pub fn print(reports: &[TurnReport], out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "Turn  Guess  Before  After   Bits  Expected  Best           Skill   Luck  Bot"
+    )?;
+
+    for (idx, report) in reports.iter().enumerate() {
+        writeln!(
+            out,
+            "{:>4}  {}  {:>6}  {:>5}  {:>5.2}  {:>8.2}  {} ({:>5.2})  {:>5.0}  {:>+5.2}  {}",
+            idx + 1,
+            report.guess.to_uppercase(),
+            report.before,
+            report.after,
+            report.gained,
+            report.expected,
+            report.best.0.to_uppercase(),
+            report.best.1,
+            report.skill(),
+            report.luck(),
+            report.bot.to_uppercase()
+        )?;
+    }
+
+    if !reports.is_empty() {
+        let skill = reports.iter().map(|e| e.skill()).sum::<f64>()
+            / reports.len() as f64;
+        let luck = reports.iter().map(|e| e.luck()).sum::<f64>();
+
+        writeln!(out)?;
+        writeln!(out, "Skill: {:.0} (average)", skill)?;
+        writeln!(out, "Luck: {:+.2} bits (total)", luck)?;
+    }
+    Ok(())
+}
+
+// Parses a game transcript: one "word hints" line per turn.
+
+fn parse_transcript(
+    name: &str, text: &str, len: usize,
+) -> io::Result<Vec<(String, Vec<Hint>)>> {
+    let mut history = vec![];
+
+    for (line, entry) in text.lines().enumerate() {
+        let entry = entry.trim();
+
+        if entry.is_empty() || entry.starts_with('#') {
+            continue;
+        }
+
+        let error = |msg: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: {}", name, line + 1, msg),
+            )
+        };
+
+        if entry.split_whitespace().count() != 2 {
+            return Err(error("expected a word and its hints".to_string()));
+        }
+
+        match input::parse(entry, len, 0) {
+            Ok(input::Input::Played(word, hints)) => {
+                history.push((word, hints))
+            }
+            Ok(_) => {
+                return Err(error("expected a word and its hints".to_string()))
+            }
+            Err(e) => return Err(error(e)),
+        }
+    }
+
+    if history.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: the game doesn't have any guesses", name),
+        ));
+    }
+    Ok(history)
+}
+
+// Analyzes the game in a transcript. The pattern matrix, if given,
+// is used to score guesses and apply the hints.
+
+pub fn run(
+    opts: &Options, lists: &dictionary::WordLists, strategy: Box<dyn Strategy>,
+    rng: &mut dyn RngCore, hard: bool, matrix: Option<&Matrix>,
+) -> io::Result<()> {
+    let (name, text) = match &opts.file {
+        Some(path) => (
+            path.display().to_string(),
+            fs::read_to_string(path).map_err(|e| {
+                io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+            })?,
+        ),
+        None => {
+            let mut text = String::new();
+
+            io::stdin().read_to_string(&mut text)?;
+            ("stdin".to_string(), text)
+        }
+    };
+    let history = parse_transcript(&name, &text, lists.word_len())?;
+    let tables = Tables {
+        vocab: &lists.vocabulary(),
+        guesses: &lists.guesses(),
+        gt: &lists.green_table(),
+        ft: &lists.freq_table(),
+        matrix,
+    };
+    let reports = analyze(&history, &tables, strategy, rng, hard)?;
+
+    print(&reports, &mut io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transcript() {
+        let history = parse_transcript(
+            "test",
+            "# a game\ncamel bbygb\n\nOTTER ggggg\n",
+            5,
+        )
+        .unwrap();
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].0, "otter");
+
+        let error =
+            |text| parse_transcript("test", text, 5).unwrap_err().to_string();
+
+        assert_eq!(error("camel\n"), "test:1: expected a word and its hints");
+        assert_eq!(error("top 3\n"), "test:1: expected a word and its hints");
+        assert_eq!(
+            error("camel bbygx\n"),
+            "test:1: only letters in hints are B, Y, and G"
+        );
+        assert_eq!(error("\n"), "test: the game doesn't have any guesses");
+    }
+}
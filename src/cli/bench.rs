@@ -0,0 +1,352 @@
+use clap::ArgEnum;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use serde::Serialize;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use wordlebot::matrix::{self, Matrix};
+use wordlebot::strategy::{self, Strategy, Turn};
+use wordlebot::{dictionary, feedback, Hint};
+
+#[derive(Copy, Clone, PartialEq, Eq, ArgEnum, Debug)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    #[clap(
+        long,
+        default_value_t = 1,
+        help = "Number of games to play per word",
+        long_help = "Word picking is random, so a single game per word can be misleading. This option plays each word several times and reports on all the games."
+    )]
+    runs: usize,
+
+    #[clap(
+        long,
+        default_value_t = 10,
+        help = "Number of worst-case words to report"
+    )]
+    worst: usize,
+
+    #[clap(
+        short,
+        long,
+        arg_enum,
+        default_value_t = Format::Table,
+        help = "Choose the report format",
+        long_help = "The table format is meant for people. The JSON and CSV formats are meant to be saved and compared against runs made with other versions of wordlebot."
+    )]
+    format: Format,
+}
+
+// Holds the results of the games played against one word.
+
+#[derive(Serialize, Debug)]
+struct WordResult {
+    word: &'static str,
+    guesses: Vec<usize>,
+    unsolved: usize,
+}
+
+impl WordResult {
+    fn max(&self) -> usize {
+        self.guesses.iter().copied().max().unwrap_or(0)
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Report {
+    games: usize,
+    runs: usize,
+    seed: u64,
+    mean: f64,
+    distribution: [usize; 6],
+    failures: usize,
+    unsolved: usize,
+    worst: Vec<(&'static str, usize)>,
+    seconds: f64,
+    words: Vec<WordResult>,
+}
+
+impl Report {
+    fn new(
+        words: Vec<WordResult>, runs: usize, seed: u64, worst: usize,
+        elapsed: Duration,
+    ) -> Report {
+        let mut distribution = [0; 6];
+        let mut failures = 0;
+        let mut total = 0;
+        let mut solved = 0;
+
+        for guesses in words.iter().flat_map(|e| e.guesses.iter()) {
+            if *guesses <= 6 {
+                distribution[guesses - 1] += 1
+            } else {
+                failures += 1
+            }
+            total += guesses;
+            solved += 1;
+        }
+
+        let unsolved = words.iter().map(|e| e.unsolved).sum();
+
+        // Sort a copy of the results so the words needing the most
+        // guesses come first. Ties are left in alphabetical order.
+
+        let mut by_max: Vec<&WordResult> = words.iter().collect();
+
+        by_max.sort_by_key(|e| std::cmp::Reverse(e.max()));
+
+        let worst = by_max
+            .iter()
+            .take(worst)
+            .map(|e| (e.word, e.max()))
+            .collect();
+
+        Report {
+            games: solved + unsolved,
+            runs,
+            seed,
+            mean: if solved > 0 {
+                total as f64 / solved as f64
+            } else {
+                0.0
+            },
+            distribution,
+            failures,
+            unsolved,
+            worst,
+            seconds: elapsed.as_secs_f64(),
+            words,
+        }
+    }
+
+    fn print_table(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "Games played: {} ({} per word)", self.games, self.runs)?;
+        writeln!(out, "Seed: {}", self.seed)?;
+        writeln!(out, "Mean guesses: {:.3}", self.mean)?;
+        writeln!(out)?;
+        writeln!(out, "Guesses     Games")?;
+
+        for (idx, count) in self.distribution.iter().enumerate() {
+            writeln!(out, "{:>7}  {:>8}", idx + 1, count)?;
+        }
+        writeln!(out, "{:>7}  {:>8}", ">6", self.failures)?;
+
+        if self.unsolved > 0 {
+            writeln!(out, "{:>7}  {:>8}", "none", self.unsolved)?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "Worst words:")?;
+
+        for (word, guesses) in &self.worst {
+            writeln!(out, "  {} ({})", word.to_uppercase(), guesses)?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "Wall time: {:.2}s", self.seconds)
+    }
+
+    // The CSV report has a row for each word so two runs can be
+    // compared with `diff`.
+
+    fn print_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "word,games,mean,max,unsolved")?;
+
+        for result in &self.words {
+            let games = result.guesses.len();
+            let mean = if games > 0 {
+                result.guesses.iter().sum::<usize>() as f64 / games as f64
+            } else {
+                0.0
+            };
+
+            writeln!(
+                out,
+                "{},{},{:.3},{},{}",
+                result.word,
+                games,
+                mean,
+                result.max(),
+                result.unsolved
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Plays a game against `answer`, starting with the vocabulary
+// `vocab`, and returns the number of guesses it took. If the
+// vocabulary runs out before the word is found, `None` is returned.
+// That can only happen if there's a bug in the way hints are
+// processed. `apply` reduces the vocabulary using a guess's hints.
+// The strategy can look hints up in `matrix`.
+
+fn play(
+    answer: &str, mut vocab: dictionary::Words, strategy: &mut dyn Strategy,
+    rng: &mut dyn RngCore, guesses: &dictionary::Words,
+    matrix: Option<&Matrix>,
+    apply: &dyn Fn(dictionary::Words, &str, &[Hint]) -> dictionary::Words,
+) -> Option<usize> {
+    let mut history = Vec::with_capacity(6);
+
+    while vocab.total() > 0 {
+        let guess = strategy.pick(
+            &Turn {
+                candidates: &vocab,
+                guesses,
+                history: &history,
+                matrix,
+            },
+            rng,
+        );
+        let hints = feedback::score(guess, answer);
+
+        if hints.iter().all(|e| *e == Hint::Green) {
+            return Some(history.len() + 1);
+        }
+
+        vocab = apply(vocab, guess, &hints);
+        history.push((guess.to_string(), hints));
+    }
+    None
+}
+
+// Plays every word in the vocabulary and reports how well wordlebot
+// did. Each game gets its own random number generator, seeded from
+// `seed` and the game's position in the run, so any game can be
+// replayed on its own. If `hard` is set, the games are played by
+// hard mode rules. The pattern matrix, if given, is used to score
+// guesses and apply the hints.
+
+pub fn run(
+    opts: &Options, lists: &dictionary::WordLists,
+    mut strategy: Box<dyn Strategy>, seed: Option<u64>, hard: bool,
+    matrix: Option<&Matrix>,
+) -> io::Result<()> {
+    if hard {
+        strategy = Box::new(strategy::Hard::new(strategy, false))
+    }
+
+    let seed = seed.unwrap_or_else(rand::random);
+    let guesses = lists.guesses();
+    let grn_tbl = lists.green_table();
+    let frq_tbl = lists.freq_table();
+    let apply = |vocab, guess: &str, hints: &[Hint]| {
+        matrix::process_hints(matrix, vocab, &grn_tbl, &frq_tbl, guess, hints)
+    };
+    let start = Instant::now();
+
+    let results: Vec<WordResult> = lists
+        .vocabulary()
+        .iter()
+        .enumerate()
+        .map(|(idx, word)| {
+            let mut result = WordResult {
+                word,
+                guesses: Vec::with_capacity(opts.runs),
+                unsolved: 0,
+            };
+
+            for run in 0..opts.runs {
+                let game = (idx * opts.runs + run) as u64;
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(game));
+
+                match play(
+                    word,
+                    lists.vocabulary(),
+                    strategy.as_mut(),
+                    &mut rng,
+                    &guesses,
+                    matrix,
+                    &apply,
+                ) {
+                    Some(guesses) => result.guesses.push(guesses),
+                    None => result.unsolved += 1,
+                }
+            }
+            result
+        })
+        .collect();
+
+    let report =
+        Report::new(results, opts.runs, seed, opts.worst, start.elapsed());
+    let mut out = io::stdout().lock();
+
+    match opts.format {
+        Format::Table => report.print_table(&mut out),
+        Format::Csv => report.print_csv(&mut out),
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, &report)?;
+            writeln!(out)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play() {
+        let lists = dictionary::WordLists::new();
+        let gt = lists.green_table();
+        let ft = lists.freq_table();
+        let guesses = lists.guesses();
+        let mut strategy = strategy::build(strategy::DEFAULT).unwrap();
+        let apply = |vocab, guess: &str, hints: &[Hint]| {
+            wordlebot::process_hints(vocab, &gt, &ft, guess, hints)
+        };
+
+        for word in ["camel", "otter", "tatty", "geese"] {
+            let mut game = |seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+
+                play(
+                    word,
+                    lists.vocabulary(),
+                    strategy.as_mut(),
+                    &mut rng,
+                    &guesses,
+                    None,
+                    &apply,
+                )
+            };
+            let guesses_needed = game(0);
+
+            assert!(guesses_needed.is_some());
+
+            // The same seed should play the same game.
+
+            assert_eq!(guesses_needed, game(0));
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let words = vec![
+            WordResult {
+                word: "camel",
+                guesses: vec![3, 4],
+                unsolved: 0,
+            },
+            WordResult {
+                word: "otter",
+                guesses: vec![7],
+                unsolved: 1,
+            },
+        ];
+        let report = Report::new(words, 2, 0, 1, Duration::from_secs(1));
+
+        assert_eq!(report.games, 4);
+        assert_eq!(report.distribution, [0, 0, 1, 1, 0, 0]);
+        assert_eq!(report.failures, 1);
+        assert_eq!(report.unsolved, 1);
+        assert_eq!(report.mean, 14.0 / 3.0);
+        assert_eq!(report.worst, vec![("otter", 7)]);
+    }
+}
@@ -0,0 +1,226 @@
+use wordlebot::Hint;
+
+// The responses a user can give at the hints prompt. Besides the
+// hints for the guess, there are a few commands for fixing mistakes
+// and looking at wordlebot's state.
+
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    // The hints for the guess.
+    Hints(Vec<Hint>),
+
+    // Go back the given number of turns and re-enter the hints for
+    // the guess made then.
+    Back(usize),
+
+    // Wordle didn't accept the guess. Pick another one for this turn
+    // without changing the vocabulary.
+    Skip,
+
+    // Wordle doesn't know the guess. It's never guessed again.
+    Reject,
+
+    // The user played a different word. Its hints are entered next.
+    Use(String),
+
+    // The user played a word and got these hints for it.
+    Played(String, Vec<Hint>),
+
+    // Show the words that could still be the answer.
+    List,
+
+    // Show the given number of guesses, best first.
+    Top(usize),
+
+    // Show a summary of the game so far.
+    Stats,
+
+    // Show the commands.
+    Help,
+
+    // End the session.
+    Quit,
+}
+
+// The help shown for the `help` command.
+
+pub const HELP: &str = "\
+Enter the hints for the guess (B, Y or G for each letter), a word you
+played followed by its hints (e.g. \"crane bygbb\") or one of:
+  undo       re-enter the hints for the previous guess
+  :back N    re-enter the hints starting N guesses ago
+  skip       Wordle didn't accept the guess; pick another one
+  reject     Wordle doesn't know the guess; never guess it again
+  use WORD   you played WORD instead; enter its hints next
+  list       show the words that could still be the answer
+  top [N]    show the N (default 5) most informative guesses
+  stats      show a summary of the game so far
+  help       show this message
+  quit       stop playing";
+
+// The number of suggestions `top` shows if it isn't given a number.
+
+const TOP: usize = 5;
+
+// Parses the optional count given to a command. `default` is used if
+// there isn't one. Counts must be positive.
+
+fn count(arg: Option<&str>, default: usize) -> Result<usize, String> {
+    match arg {
+        None => Ok(default),
+        Some(arg) => match arg.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("\"{}\" isn't a positive number", arg)),
+        },
+    }
+}
+
+// Parses a line entered at the hints prompt. `len` is the length of
+// the guess and `turns` is how many turns have been played, which
+// limits how far back the user can go.
+
+pub fn parse(input: &str, len: usize, turns: usize) -> Result<Input, String> {
+    // Commands are case-insensitive so they're matched against the
+    // lowercase version of the input.
+
+    let lower = input.trim().to_lowercase();
+    let mut words = lower.split_whitespace();
+    let command = words.next().unwrap_or("");
+    let arg = words.next();
+
+    if words.next().is_some() {
+        return Err(format!("too many arguments for \"{}\"", command));
+    }
+
+    let input = match (command, arg) {
+        ("undo", None) => Input::Back(1),
+        (":back", arg) => Input::Back(count(arg, 1)?),
+        ("skip", None) => Input::Skip,
+        ("reject", None) => Input::Reject,
+        ("use", Some(word)) => {
+            check_word(word, len)?;
+            Input::Use(word.to_string())
+        }
+        ("use", None) => return Err("use which word?".to_string()),
+        ("list", None) => Input::List,
+        ("top", arg) => Input::Top(count(arg, TOP)?),
+        ("stats", None) => Input::Stats,
+        ("help" | "?", None) => Input::Help,
+        ("quit" | "exit", None) => Input::Quit,
+        (_, None) => return hints(command, len).map(Input::Hints),
+        (word, Some(arg)) => {
+            check_word(word, len)?;
+            Input::Played(word.to_string(), hints(arg, len)?)
+        }
+    };
+
+    // Going back can't go past the first guess.
+
+    match input {
+        Input::Back(n) if n > turns => Err(if turns == 0 {
+            "there are no earlier turns to go back to".to_string()
+        } else {
+            format!("can only go back {} turn(s)", turns)
+        }),
+        input => Ok(input),
+    }
+}
+
+// Makes sure a word the user played could be a guess.
+
+fn check_word(word: &str, len: usize) -> Result<(), String> {
+    if word.len() == len && word.chars().all(|c| c.is_ascii_lowercase()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "\"{}\" isn't a command or a word of {} letters",
+            word.to_uppercase(),
+            len
+        ))
+    }
+}
+
+// Parses a hint string.
+
+pub fn hints(input: &str, len: usize) -> Result<Vec<Hint>, String> {
+    // The input *must* have a hint for each letter.
+
+    if input.chars().count() != len {
+        return Err(format!(
+            "hints must contain {} characters (enter \"help\" for commands)",
+            len
+        ));
+    }
+
+    // The input can only contain the letters B, Y, and G.
+
+    input
+        .chars()
+        .map(Hint::try_from)
+        .collect::<Result<Vec<Hint>, ()>>()
+        .map_err(|_| "only letters in hints are B, Y, and G".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Hint::{Black as B, Green as G, Yellow as Y};
+
+    #[test]
+    fn test_hints() {
+        assert_eq!(
+            parse("bygbb\n", 5, 0),
+            Ok(Input::Hints(vec![B, Y, G, B, B]))
+        );
+        assert_eq!(parse(" GGGG ", 4, 0), Ok(Input::Hints(vec![G; 4])));
+        assert!(parse("BYGB", 5, 0).is_err());
+        assert!(parse("BYGBX", 5, 0).is_err());
+        assert!(parse("", 5, 0).is_err());
+    }
+
+    #[test]
+    fn test_back() {
+        assert_eq!(parse("undo", 5, 2), Ok(Input::Back(1)));
+        assert_eq!(parse(":back", 5, 2), Ok(Input::Back(1)));
+        assert_eq!(parse(":back 2", 5, 2), Ok(Input::Back(2)));
+        assert!(parse(":back 3", 5, 2).is_err());
+        assert!(parse(":back 0", 5, 2).is_err());
+        assert!(parse(":back x", 5, 2).is_err());
+        assert!(parse("undo", 5, 0).is_err());
+        assert!(parse("undo 1", 5, 2).is_err());
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(parse("skip", 5, 0), Ok(Input::Skip));
+        assert_eq!(parse("SKIP", 5, 0), Ok(Input::Skip));
+        assert_eq!(parse("reject", 5, 0), Ok(Input::Reject));
+        assert_eq!(parse("use Crane", 5, 0), Ok(Input::Use("crane".into())));
+        assert!(parse("use", 5, 0).is_err());
+        assert!(parse("use cranes", 5, 0).is_err());
+        assert!(parse("use cr4ne", 5, 0).is_err());
+        assert!(parse("use crane slate", 5, 0).is_err());
+        assert_eq!(parse("list", 5, 0), Ok(Input::List));
+        assert_eq!(parse("top", 5, 0), Ok(Input::Top(TOP)));
+        assert_eq!(parse("top 10", 5, 0), Ok(Input::Top(10)));
+        assert!(parse("top 0", 5, 0).is_err());
+        assert!(parse("list 3", 5, 0).is_err());
+        assert_eq!(parse("stats", 5, 0), Ok(Input::Stats));
+        assert_eq!(parse("help", 5, 0), Ok(Input::Help));
+        assert_eq!(parse("?", 5, 0), Ok(Input::Help));
+        assert_eq!(parse("quit", 5, 0), Ok(Input::Quit));
+        assert_eq!(parse("exit", 5, 0), Ok(Input::Quit));
+    }
+
+    #[test]
+    fn test_played() {
+        assert_eq!(
+            parse("CRANE bygbb", 5, 0),
+            Ok(Input::Played("crane".into(), vec![B, Y, G, B, B]))
+        );
+        assert!(parse("crane bygb", 5, 0).is_err());
+        assert!(parse("crane bygbx", 5, 0).is_err());
+        assert!(parse("cranes bygbb", 5, 0).is_err());
+        assert!(parse("crane bygbb x", 5, 0).is_err());
+    }
+}
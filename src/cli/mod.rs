@@ -0,0 +1,9 @@
+// The commands of the `wordlebot` program other than playing a game.
+// They're built on the library and live in the program, since other
+// tools have no use for their options.
+
+pub mod analysis;
+pub mod bench;
+pub mod input;
+pub mod share;
+pub mod tree;
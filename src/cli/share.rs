@@ -0,0 +1,316 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use wordlebot::{dictionary, feedback, to_hint_string, Hint};
+
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    #[clap(
+        value_name = "FILE",
+        help = "The share text to read",
+        long_help = "A file holding the text Wordle shares once a puzzle is done: a header like \"Wordle 1,234 4/6\" followed by a row of colored squares for each guess. Either theme can be used. If not given, the text is read from stdin."
+    )]
+    file: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "WORD",
+        help = "List the guesses that fit each row",
+        long_help = "The answer to the puzzle. For each row, the words that would have gotten the row's hints are listed."
+    )]
+    answer: Option<String>,
+
+    #[clap(
+        long,
+        default_value_t = 20,
+        help = "Set the number of words listed per row"
+    )]
+    limit: usize,
+}
+
+// The contents of a share block.
+
+#[derive(Debug, PartialEq)]
+pub struct Share {
+    // The header, if there was one.
+    pub header: Option<Header>,
+
+    // The hints for each guess.
+    pub rows: Vec<Vec<Hint>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Header {
+    // The puzzle number. wordlebot's own share text uses "???".
+    pub puzzle: Option<u32>,
+
+    // The number of guesses used, or `None` if the puzzle wasn't
+    // solved ("X/6").
+    pub guesses: Option<usize>,
+
+    // Set if the game was played in hard mode ("4/6*").
+    pub hard: bool,
+}
+
+// Converts a square back to a hint. Both themes are accepted, as is
+// the white square Wordle uses for misses in its light theme.
+
+fn from_char(ch: char) -> Option<Hint> {
+    match ch {
+        '⬛' | '⬜' => Some(Hint::Black),
+        '🟨' | '🟦' => Some(Hint::Yellow),
+        '🟩' | '🟧' => Some(Hint::Green),
+        _ => None,
+    }
+}
+
+// Parses a header like "Wordle 1,234 4/6*".
+
+fn parse_header(line: &str) -> Result<Header, String> {
+    let error = || format!("\"{}\" isn't a Wordle header", line);
+    let fields: Vec<&str> = line.split_whitespace().collect();
+
+    match fields[..] {
+        [name, puzzle, score] if name == "Wordle" || name == "WordleBot" => {
+            let puzzle = match puzzle {
+                "???" => None,
+                puzzle => {
+                    Some(puzzle.replace(',', "").parse().map_err(|_| error())?)
+                }
+            };
+            let (score, hard) = match score.strip_suffix('*') {
+                Some(score) => (score, true),
+                None => (score, false),
+            };
+            let guesses = match score.strip_suffix("/6").ok_or_else(error)? {
+                "X" | "x" => None,
+                n => Some(n.parse().map_err(|_| error())?),
+            };
+
+            Ok(Header {
+                puzzle,
+                guesses,
+                hard,
+            })
+        }
+        _ => Err(error()),
+    }
+}
+
+// Parses share text. Blank lines are ignored, as are the variation
+// selectors some apps add after each square. The header is optional
+// but, if it's there, the number of rows has to agree with it.
+
+pub fn parse(text: &str) -> Result<Share, String> {
+    let mut lines = text
+        .lines()
+        .map(|e| e.replace('\u{fe0f}', "").trim().to_string())
+        .filter(|e| !e.is_empty())
+        .peekable();
+
+    let header = match lines.peek() {
+        Some(line) if line.starts_with("Wordle") => {
+            let header = parse_header(line)?;
+
+            lines.next();
+            Some(header)
+        }
+        _ => None,
+    };
+
+    let mut rows: Vec<Vec<Hint>> = vec![];
+
+    for line in lines {
+        let row = line
+            .chars()
+            .map(from_char)
+            .collect::<Option<Vec<Hint>>>()
+            .ok_or_else(|| format!("\"{}\" isn't a row of squares", line))?;
+
+        if let Some(first) = rows.first() {
+            if first.len() != row.len() {
+                return Err(format!(
+                    "row {} has {} squares but row 1 has {}",
+                    rows.len() + 1,
+                    row.len(),
+                    first.len()
+                ));
+            }
+        }
+        rows.push(row)
+    }
+
+    if rows.is_empty() {
+        return Err("there aren't any rows of squares".to_string());
+    }
+
+    let solved = rows.last().unwrap().iter().all(|e| *e == Hint::Green);
+
+    if let Some(header) = &header {
+        match header.guesses {
+            Some(n) if n != rows.len() || !solved => {
+                return Err(format!(
+                    "the header says {} guesses but there are {} rows{}",
+                    n,
+                    rows.len(),
+                    if solved {
+                        ""
+                    } else {
+                        " and the last isn't solved"
+                    }
+                ))
+            }
+            None if solved => {
+                return Err("the header says the puzzle wasn't solved but \
+                            the last row is"
+                    .to_string())
+            }
+            _ => (),
+        }
+    }
+    Ok(Share { header, rows })
+}
+
+// Returns the words that would get `hints` if `answer` was the
+// answer.
+
+pub fn matching_guesses(
+    hints: &[Hint], answer: &str, guesses: &dictionary::Words,
+) -> Vec<&'static str> {
+    guesses
+        .iter()
+        .filter(|e| feedback::score(e, answer) == hints)
+        .collect()
+}
+
+// Reads share text and prints the hints of each row. If the answer
+// was given, the words that fit each row are printed too. Words
+// that could be the answer are listed before the other guesses since
+// people tend to play them.
+
+pub fn run(opts: &Options, lists: &dictionary::WordLists) -> io::Result<()> {
+    let text = match &opts.file {
+        Some(path) => fs::read_to_string(path).map_err(|e| {
+            io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+        })?,
+        None => {
+            let mut text = String::new();
+
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+    let share = parse(&text)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let answer = opts.answer.as_ref().map(|e| e.trim().to_lowercase());
+    let guesses = lists.guesses();
+    let vocab = lists.vocabulary();
+    let mut out = io::stdout().lock();
+
+    if let Some(answer) = &answer {
+        if answer.len() != share.rows[0].len()
+            || !answer.chars().all(|c| c.is_ascii_lowercase())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "\"{}\" isn't a word with a letter for each square",
+                    answer.to_uppercase()
+                ),
+            ));
+        }
+    }
+
+    for (idx, row) in share.rows.iter().enumerate() {
+        write!(out, "{} {}", idx + 1, to_hint_string(row))?;
+
+        if let Some(answer) = &answer {
+            let mut words = matching_guesses(row, answer, &guesses);
+
+            words.sort_by_key(|e| !vocab.contains(e));
+
+            let shown: Vec<String> = words
+                .iter()
+                .take(opts.limit)
+                .map(|e| e.to_uppercase())
+                .collect();
+
+            write!(out, "  {} word(s): {}", words.len(), shown.join(" "))?;
+
+            if words.len() > shown.len() {
+                write!(out, " ...")?;
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Hint::{Black as B, Green as G, Yellow as Y};
+
+    #[test]
+    fn test_parse() {
+        let share =
+            parse("Wordle 1,234 3/6*\n\n⬛⬛🟨⬛⬛\n🟩🟩⬛🟩⬛\n🟩🟩🟩🟩🟩\n")
+                .unwrap();
+
+        assert_eq!(
+            share.header,
+            Some(Header {
+                puzzle: Some(1234),
+                guesses: Some(3),
+                hard: true,
+            })
+        );
+        assert_eq!(
+            share.rows,
+            vec![vec![B, B, Y, B, B], vec![G, G, B, G, B], vec![G; 5]]
+        );
+
+        // High contrast squares, light theme misses, variation
+        // selectors and no header.
+
+        let share = parse("⬜\u{fe0f}🟦🟧⬜⬜\n🟧🟧🟧🟧🟧").unwrap();
+
+        assert_eq!(share.header, None);
+        assert_eq!(share.rows, vec![vec![B, Y, G, B, B], vec![G; 5]]);
+
+        // wordlebot's own share text and an unsolved puzzle.
+
+        assert!(parse("WordleBot ??? 1/6\n🟩🟩🟩🟩🟩").is_ok());
+        assert_eq!(
+            parse("Wordle 12 X/6\n⬛⬛⬛⬛⬛")
+                .unwrap()
+                .header
+                .unwrap()
+                .guesses,
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("Wordle 1,234 3/6\n").is_err());
+        assert!(parse("Wordle 1,234 2/6\n🟩🟩🟩🟩🟩").is_err());
+        assert!(parse("Wordle 1,234 X/6\n🟩🟩🟩🟩🟩").is_err());
+        assert!(parse("Wordle one 1/6\n🟩🟩🟩🟩🟩").is_err());
+        assert!(parse("⬛⬛⬛⬛\n🟩🟩🟩🟩🟩").is_err());
+        assert!(parse("⬛⬛x⬛⬛").is_err());
+    }
+
+    #[test]
+    fn test_matching_guesses() {
+        let guesses = dictionary::Words::new(&["camel", "hazel", "otter"]);
+
+        assert_eq!(
+            matching_guesses(&[B, G, B, G, G], "camel", &guesses),
+            vec!["hazel"]
+        );
+        assert_eq!(matching_guesses(&[G; 5], "camel", &guesses), vec!["camel"]);
+        assert!(matching_guesses(&[Y; 5], "camel", &guesses).is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+use clap::ArgEnum;
+use rand::{rngs::StdRng, SeedableRng};
+use std::io::{self, Write};
+use wordlebot::dictionary::{WordLists, Words};
+use wordlebot::matrix::Matrix;
+use wordlebot::strategy::{self, Strategy, Turn};
+use wordlebot::tree::Tree;
+
+#[derive(Copy, Clone, PartialEq, Eq, ArgEnum, Debug)]
+pub enum Method {
+    Greedy,
+    Exhaustive,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ArgEnum, Debug)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    #[clap(
+        about = "Build a solving tree",
+        long_about = "Works out what to guess after every set of hints, starting with the opener, until every answer is solved. The tree is written to stdout and a summary of it to stderr."
+    )]
+    Build(BuildOptions),
+}
+
+#[derive(clap::Args, Debug)]
+struct BuildOptions {
+    #[clap(
+        long,
+        value_name = "WORD",
+        help = "Set the first guess",
+        long_help = "The guess every game in the tree starts with. If not given, it's the word the strategy would open with."
+    )]
+    opener: Option<String>,
+
+    #[clap(
+        short,
+        long,
+        arg_enum,
+        default_value_t = Method::Greedy,
+        help = "Choose how the guesses are found",
+        long_help = "The greedy method plays the guess the strategy picks at each node. The exhaustive method tries the most informative guesses at each node and keeps the ones that take the fewest guesses in total. It ignores --strategy, which only picks the opener if --opener isn't given."
+    )]
+    method: Method,
+
+    #[clap(
+        long,
+        default_value_t = 10,
+        value_parser = at_least_one,
+        help = "Number of guesses tried at each node",
+        long_help = "How many guesses the exhaustive method tries at each node, taken from the ones expected to tell the most. Larger values can find better trees but take longer."
+    )]
+    width: usize,
+
+    #[clap(
+        long,
+        default_value_t = 6,
+        value_parser = at_least_one,
+        help = "Most guesses any answer may take",
+        long_help = "The greedy method leaves out of the tree the answers it doesn't solve in this many guesses. The exhaustive method only builds trees that solve every answer in this many guesses."
+    )]
+    max_guesses: usize,
+
+    #[clap(
+        short,
+        long,
+        arg_enum,
+        default_value_t = Format::Text,
+        help = "Choose the format of the tree",
+        long_help = "The text format has a line for each answer listing the guesses and hints that solve it, like the tree files of other Wordle solvers. The JSON format nests each node's branches inside it."
+    )]
+    format: Format,
+}
+
+// Parses a count that has to be at least one.
+
+fn at_least_one(arg: &str) -> Result<usize, String> {
+    match arg.parse() {
+        Ok(0) => Err("it has to be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(format!("{}", e)),
+    }
+}
+
+// Writes a summary of the tree: how many guesses the answers take.
+
+fn summary(
+    tree: &Tree, answers: &Words, out: &mut impl Write,
+) -> io::Result<()> {
+    let mut counts: Vec<usize> = vec![];
+
+    for path in answers.iter().filter_map(|e| tree.solve(e)) {
+        if counts.len() < path.len() {
+            counts.resize(path.len(), 0)
+        }
+        counts[path.len() - 1] += 1
+    }
+
+    let solved: usize = counts.iter().sum();
+    let total: usize =
+        counts.iter().enumerate().map(|(ii, e)| (ii + 1) * e).sum();
+
+    writeln!(out, "Opener: {}", tree.guess.to_uppercase())?;
+    writeln!(out, "Answers solved: {} of {}", solved, answers.total())?;
+    writeln!(out, "Total guesses: {}", total)?;
+    writeln!(
+        out,
+        "Mean guesses: {:.3}",
+        if solved > 0 {
+            total as f64 / solved as f64
+        } else {
+            0.0
+        }
+    )?;
+    writeln!(out)?;
+    writeln!(out, "Guesses   Answers")?;
+
+    for (idx, count) in counts.iter().enumerate() {
+        writeln!(out, "{:>7}  {:>8}", idx + 1, count)?;
+    }
+    Ok(())
+}
+
+// Builds a tree and writes it to stdout. If `hard` is set, the tree
+// follows hard mode rules. `seed` seeds the strategy's random number
+// generator, so a random strategy builds the same tree each time. The
+// pattern matrix, if given, is used to look up hints.
+
+fn build(
+    opts: &BuildOptions, lists: &WordLists, mut strategy: Box<dyn Strategy>,
+    seed: u64, hard: bool, matrix: Option<&Matrix>,
+) -> io::Result<()> {
+    if hard {
+        strategy = Box::new(strategy::Hard::new(strategy, false))
+    }
+
+    let answers = lists.vocabulary();
+    let guesses = lists.guesses();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let error = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+
+    let opener = match &opts.opener {
+        Some(word) => {
+            let word = word.trim().to_lowercase();
+
+            guesses.find(&word).ok_or_else(|| {
+                error(format!(
+                    "\"{}\" isn't an allowed guess",
+                    word.to_uppercase()
+                ))
+            })?
+        }
+        None => strategy.pick(
+            &Turn {
+                candidates: &answers,
+                guesses: &guesses,
+                history: &[],
+                matrix,
+            },
+            &mut rng,
+        ),
+    };
+    let tree = match opts.method {
+        Method::Greedy => Tree::greedy(
+            opener,
+            &answers,
+            &guesses,
+            matrix,
+            opts.max_guesses,
+            strategy.as_mut(),
+            &mut rng,
+        )
+        .map_err(error)?,
+        Method::Exhaustive => Tree::exhaustive(
+            opener,
+            &answers,
+            &guesses,
+            matrix,
+            opts.width,
+            opts.max_guesses,
+            hard,
+        )
+        .ok_or_else(|| {
+            error(format!(
+                "no tree starting with {} solves every answer in {} guesses",
+                opener.to_uppercase(),
+                opts.max_guesses
+            ))
+        })?,
+    };
+
+    let mut out = io::stdout().lock();
+
+    match opts.format {
+        Format::Text => tree.write_text(&answers, &mut out)?,
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, &tree)?;
+            writeln!(out)?
+        }
+    }
+    summary(&tree, &answers, &mut io::stderr().lock())
+}
+
+pub fn run(
+    opts: &Options, lists: &WordLists, strategy: Box<dyn Strategy>, seed: u64,
+    hard: bool, matrix: Option<&Matrix>,
+) -> io::Result<()> {
+    match &opts.command {
+        Command::Build(opts) => {
+            build(opts, lists, strategy, seed, hard, matrix)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at_least_one() {
+        assert_eq!(at_least_one("1"), Ok(1));
+        assert_eq!(at_least_one("10"), Ok(10));
+        assert!(at_least_one("0").is_err());
+        assert!(at_least_one("-1").is_err());
+        assert!(at_least_one("x").is_err());
+    }
+}
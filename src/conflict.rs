@@ -0,0 +1,384 @@
+use crate::matrix::Matrix;
+use crate::{dictionary, feedback, Hint, Map};
+use itertools::Itertools;
+
+// Explains why no word fits the clues. This is used when the
+// vocabulary runs out, which almost always means the user made a
+// mistake entering a clue.
+
+#[derive(Debug, PartialEq)]
+pub struct Conflict {
+    // The smallest set of turns (indexes into the history) whose
+    // clues can't all be right.
+    pub turns: Vec<usize>,
+
+    // Descriptions of the rules broken by those turns. It's empty if
+    // the clues are consistent with each other but no word in the
+    // vocabulary fits them.
+    pub reasons: Vec<String>,
+
+    // Single clue changes that would leave words in the vocabulary.
+    pub fixes: Vec<Fix>,
+}
+
+// A change to one clue which would restore the vocabulary.
+
+#[derive(Debug, PartialEq)]
+pub struct Fix {
+    pub turn: usize,
+    pub position: usize,
+    pub hint: Hint,
+    pub words: usize,
+}
+
+type Turn<'a> = (&'a str, &'a [Hint]);
+
+// Applies the clues of the given turns to `vocab`, keeping exactly
+// the words that would give them. The matrix does that with a look-up
+// per word if it can be used. `process_hints()` can keep a few words
+// that don't fit, so a game using it runs out of words no sooner
+// than this does.
+
+fn apply<'a>(
+    vocab: &dictionary::Words, matrix: Option<&Matrix>,
+    turns: impl Iterator<Item = Turn<'a>>,
+) -> dictionary::Words {
+    turns.fold(vocab.clone(), |mut vocab, (guess, hints)| {
+        match matrix.and_then(|e| e.filter(&vocab, guess, hints)) {
+            Some(words) => words,
+            None => {
+                vocab
+                    .retain_words(|word| feedback::score(guess, word) == hints);
+                vocab
+            }
+        }
+    })
+}
+
+fn hint_name(hint: Hint) -> &'static str {
+    match hint {
+        Hint::Black => "black",
+        Hint::Yellow => "yellow",
+        Hint::Green => "green",
+    }
+}
+
+// Looks for rules broken by a set of clues. Each turn only knows
+// about its own guess so this compares each turn against the others:
+// different letters placed in the same position, a letter placed and
+// ruled out in the same position, letter counts that don't overlap
+// and more letters than fit in a word.
+
+fn find_reasons(turns: &[(usize, Turn)]) -> Vec<String> {
+    let mut reasons = vec![];
+
+    for ((t1, (g1, h1)), (t2, (g2, h2))) in
+        turns.iter().cartesian_product(turns.iter())
+    {
+        let pairs = g1.chars().zip(h1.iter()).zip(g2.chars().zip(h2.iter()));
+
+        for (idx, ((c1, h1), (c2, h2))) in pairs.enumerate() {
+            if *h1 != Hint::Green || t1 == t2 {
+                continue;
+            }
+
+            if c1 != c2 && *h2 == Hint::Green && t1 < t2 {
+                reasons.push(format!(
+                    "guess {} has a green {} in position {} but guess {} \
+                     has a green {} there",
+                    t1 + 1,
+                    c1.to_ascii_uppercase(),
+                    idx + 1,
+                    t2 + 1,
+                    c2.to_ascii_uppercase()
+                ))
+            } else if c1 == c2 && *h2 != Hint::Green {
+                reasons.push(format!(
+                    "guess {} has a green {} in position {} but guess {} \
+                     has a {} {} there",
+                    t1 + 1,
+                    c1.to_ascii_uppercase(),
+                    idx + 1,
+                    t2 + 1,
+                    hint_name(*h2),
+                    c1.to_ascii_uppercase()
+                ))
+            }
+        }
+    }
+
+    // For each letter, find the turn requiring the most instances and
+    // the turn allowing the fewest. A black hint caps the count at
+    // the number of greens and yellows the letter got in that turn.
+
+    let mut least: Map<char, (usize, usize)> = Map::new();
+    let mut most: Map<char, (usize, usize)> = Map::new();
+
+    for (turn, (guess, hints)) in turns {
+        let mut counts: Map<char, (usize, bool)> = Map::new();
+
+        for (ch, hint) in guess.chars().zip(hints.iter()) {
+            let entry = counts.entry(ch).or_insert((0, false));
+
+            if *hint == Hint::Black {
+                entry.1 = true
+            } else {
+                entry.0 += 1
+            }
+        }
+
+        for (ch, (marked, capped)) in counts {
+            if least.get(&ch).is_none_or(|e| marked > e.0) {
+                least.insert(ch, (marked, *turn));
+            }
+            if capped && most.get(&ch).is_none_or(|e| marked < e.0) {
+                most.insert(ch, (marked, *turn));
+            }
+        }
+    }
+
+    for (ch, (low, low_turn)) in &least {
+        if let Some((high, high_turn)) = most.get(ch) {
+            if low > high {
+                reasons.push(format!(
+                    "guess {} needs at least {} {}'s but guess {} allows \
+                     at most {}",
+                    low_turn + 1,
+                    low,
+                    ch.to_ascii_uppercase(),
+                    high_turn + 1,
+                    high
+                ))
+            }
+        }
+    }
+
+    let needed: usize = least.values().map(|e| e.0).sum();
+    let len = turns.first().map_or(0, |(_, (guess, _))| guess.len());
+
+    if needed > len {
+        reasons.push(format!(
+            "the clues need {} letters but words only have {}",
+            needed, len
+        ))
+    }
+    reasons
+}
+
+// Figures out which clues conflict. The smallest set of turns that
+// leaves no words is found by trying every combination of turns,
+// smallest first; a game only has a handful of turns so this is
+// cheap. Only clues in those turns can be the mistake, so those are
+// the ones tried with each of the other hints. The pattern matrix is
+// used if there is one.
+
+pub fn explain(
+    history: &[(String, Vec<Hint>)], vocab: &dictionary::Words,
+    matrix: Option<&Matrix>,
+) -> Conflict {
+    let all: Vec<(usize, Turn)> = history
+        .iter()
+        .enumerate()
+        .map(|(idx, (guess, hints))| (idx, (guess.as_str(), hints.as_slice())))
+        .collect();
+
+    let turns = (1..=all.len())
+        .flat_map(|size| all.iter().copied().combinations(size))
+        .find(|turns| {
+            apply(vocab, matrix, turns.iter().map(|e| e.1)).total() == 0
+        })
+        .unwrap_or_else(|| all.clone());
+
+    let mut fixes = vec![];
+
+    for (turn, (guess, hints)) in &turns {
+        for position in 0..hints.len() {
+            for hint in [Hint::Black, Hint::Yellow, Hint::Green] {
+                if hint == hints[position] {
+                    continue;
+                }
+
+                let mut changed = hints.to_vec();
+
+                changed[position] = hint;
+
+                let clues = all.iter().map(|(idx, clue)| {
+                    if idx == turn {
+                        (*guess, changed.as_slice())
+                    } else {
+                        *clue
+                    }
+                });
+                let words = apply(vocab, matrix, clues).total();
+
+                if words > 0 {
+                    fixes.push(Fix {
+                        turn: *turn,
+                        position,
+                        hint,
+                        words,
+                    })
+                }
+            }
+        }
+    }
+
+    Conflict {
+        reasons: find_reasons(&turns),
+        turns: turns.into_iter().map(|e| e.0).collect(),
+        fixes,
+    }
+}
+
+impl Conflict {
+    // Prints the explanation. `history` is needed to show the
+    // guesses and clues being referred to.
+
+    pub fn print(&self, history: &[(String, Vec<Hint>)]) {
+        let turns: Vec<String> = self
+            .turns
+            .iter()
+            .map(|e| {
+                format!(
+                    "{} ({} {})",
+                    e + 1,
+                    history[*e].0.to_uppercase(),
+                    crate::to_hint_string(&history[*e].1)
+                )
+            })
+            .collect();
+
+        if turns.len() == 1 {
+            println!("The clues for guess {} don't fit any word.", turns[0]);
+        } else {
+            println!(
+                "The clues for guesses {} don't fit any word together.",
+                turns.join(", ")
+            );
+        }
+
+        for reason in &self.reasons {
+            println!("  - {}", reason)
+        }
+
+        if self.fixes.is_empty() {
+            println!("Changing a single clue won't fix it.");
+            return;
+        }
+
+        println!("Changing one of these clues would leave some words:");
+
+        for fix in &self.fixes {
+            let mut hints = history[fix.turn].1.clone();
+
+            hints[fix.position] = fix.hint;
+            println!(
+                "  - guess {}, position {} to {} ({}): {} word(s)",
+                fix.turn + 1,
+                fix.position + 1,
+                hint_name(fix.hint),
+                crate::to_hint_string(&hints),
+                fix.words
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Hint::{Black as B, Green as G, Yellow as Y};
+
+    #[test]
+    fn test_explain() {
+        let words = ["camel", "hazel", "otter", "tatty", "geese"];
+        let vocab = dictionary::Words::new(&words);
+
+        // Each turn is fine on its own. The first says E is in the
+        // fourth position and the second says it isn't.
+
+        let history = vec![
+            ("camel".to_string(), vec![B, B, B, G, B]),
+            ("zzzzz".to_string(), vec![B, B, B, B, B]),
+            ("otter".to_string(), vec![B, B, B, Y, B]),
+        ];
+        let conflict = explain(&history, &vocab, None);
+
+        assert_eq!(conflict.turns, vec![0, 2]);
+        assert_eq!(
+            conflict.reasons,
+            vec!["guess 1 has a green E in position 4 but guess 3 has a \
+                 yellow E there"
+                .to_string()]
+        );
+        assert!(conflict.fixes.iter().all(|e| e.turn == 0 || e.turn == 2));
+        assert!(conflict.fixes.contains(&Fix {
+            turn: 0,
+            position: 3,
+            hint: Y,
+            words: 1,
+        }));
+    }
+
+    // The clues are replayed exactly. `process_hints()` would keep
+    // BEARD and BLADE for ABACK's hints, though both would get a
+    // green A, and then no set of turns would be found to blame.
+
+    #[test]
+    fn test_explain_exact() {
+        let vocab = dictionary::Words::new(&["beard", "blade"]);
+        let history = vec![
+            ("zzzzz".to_string(), vec![B; 5]),
+            ("aback".to_string(), vec![Y, Y, B, B, B]),
+        ];
+        let conflict = explain(&history, &vocab, None);
+
+        // Both words would give BYGBB, two clues away.
+
+        assert_eq!(conflict.turns, vec![1]);
+        assert!(conflict.fixes.is_empty());
+    }
+
+    #[test]
+    fn test_reasons() {
+        let reasons = |turns: &[(&str, &[Hint])]| {
+            find_reasons(&turns.iter().copied().enumerate().collect::<Vec<_>>())
+        };
+
+        assert!(reasons(&[("camel", &[B, B, B, B, G])]).is_empty());
+        assert_eq!(
+            reasons(&[
+                ("camel", &[B, B, B, B, G]),
+                ("hazel", &[B, B, B, B, Y])
+            ]),
+            vec![
+                "guess 1 has a green L in position 5 but guess 2 has a \
+                  yellow L there"
+            ]
+        );
+        assert_eq!(
+            reasons(&[
+                ("camel", &[G, B, B, B, B]),
+                ("hazel", &[G, B, B, B, B])
+            ]),
+            vec![
+                "guess 1 has a green C in position 1 but guess 2 has a \
+                  green H there"
+            ]
+        );
+        assert_eq!(
+            reasons(&[
+                ("tatty", &[G, B, B, B, B]),
+                ("otter", &[B, B, B, B, B])
+            ]),
+            vec!["guess 1 needs at least 1 T's but guess 2 allows at most 0"]
+        );
+        assert_eq!(
+            reasons(&[
+                ("abcde", &[Y, Y, Y, Y, Y]),
+                ("fghij", &[Y, B, B, B, B])
+            ]),
+            vec!["the clues need 6 letters but words only have 5"]
+        );
+    }
+}
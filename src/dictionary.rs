@@ -1,11 +1,23 @@
 use crate::{Map, Set};
 use itertools::Itertools;
-use std::ops::Deref;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::{fmt, fs};
 
-// List of words used by Wordle. This list was obtained from the
-// wordle-tui project.
+// The longest words the program supports. The hints for a word are
+// encoded as a base-3 number by `feedback::pattern()`, which has to
+// fit in 16 bits.
 
-const WORDS: &[&str] = &[
+pub const MAX_LEN: usize = 10;
+
+// Lists of words used by Wordle, which are the default word lists.
+// These lists were obtained from the wordle-tui project. `ANSWERS`
+// holds the words Wordle picks its puzzles from. `GUESSES` holds the
+// other words Wordle accepts as guesses; they'll never be the answer
+// but can be useful as probes.
+
+const ANSWERS: &[&str] = &[
     "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade",
     "naval", "serve", "heath", "dwarf", "model", "karma", "stink", "grade",
     "quiet", "bench", "abate", "feign", "major", "death", "fresh", "crust",
@@ -294,7 +306,10 @@ const WORDS: &[&str] = &[
     "adapt", "willy", "polka", "rayon", "skirt", "faith", "torso", "match",
     "mercy", "tepid", "sleek", "riser", "twixt", "peace", "flush", "catty",
     "login", "eject", "roger", "rival", "untie", "refit", "aorta", "adult",
-    "judge", "rower", "artsy", "rural", "shave", //
+    "judge", "rower", "artsy", "rural", "shave",
+];
+
+const GUESSES: &[&str] = &[
     "aahed", "aalii", "aargh", "aarti", "abaca", "abaci", "abacs", "abaft",
     "abaka", "abamp", "aband", "abash", "abask", "abaya", "abbas", "abbed",
     "abbes", "abcee", "abeam", "abear", "abele", "abers", "abets", "abies",
@@ -1627,115 +1642,253 @@ const WORDS: &[&str] = &[
     "zurfs", "zuzim", "zygal", "zygon", "zymes", "zymic",
 ];
 
+// The words a set can hold, in alphabetical order. A word's position
+// in the index is its bit in the sets using the index.
+
+#[derive(Debug)]
+struct Index(Vec<&'static str>);
+
+impl Index {
+    fn new(words: &[&'static str]) -> Arc<Index> {
+        Arc::new(Index(words.iter().copied().sorted().dedup().collect()))
+    }
+
+    fn position(&self, word: &str) -> Option<usize> {
+        self.0.binary_search(&word).ok()
+    }
+}
+
+// Iterates over the positions of the bits set in a block.
+
+struct Bits(u64);
+
+impl Iterator for Bits {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            None
+        } else {
+            let bit = self.0.trailing_zeros() as usize;
+
+            self.0 &= self.0 - 1;
+            Some(bit)
+        }
+    }
+}
+
 // Words is a set containing words (which are, in turn, &'static str
 // values.) This type defines operations that one would like to do
 // with a set of words.
+//
+// The set is a bitset over an index of words. Sets made from the same
+// word lists share an index so combining them is a handful of
+// bitwise operations rather than building a new tree. Sets with
+// different indexes (e.g. a set made by `new()`) can still be
+// combined; it's just slower.
 
-#[derive(PartialEq, Eq, Debug)]
-pub struct Words(Set<&'static str>);
+#[derive(Clone)]
+pub struct Words {
+    index: Arc<Index>,
+    bits: Vec<u64>,
+}
 
 impl Words {
     // Create a set of words from an array slice.
 
-    pub fn new(contents: &'static [&'static str]) -> Words {
-        Words(contents.iter().map(|e| e.deref()).collect())
+    pub fn new(contents: &[&'static str]) -> Words {
+        let index = Index::new(contents);
+        let len = index.0.len();
+
+        Words::with_index(&index, 0..len)
+    }
+
+    // Creates a set holding the words at the given positions of
+    // `index`.
+
+    fn with_index(
+        index: &Arc<Index>, positions: impl Iterator<Item = usize>,
+    ) -> Words {
+        let mut words = Words {
+            index: index.clone(),
+            bits: vec![0; index.0.len().div_ceil(64)],
+        };
+
+        positions.for_each(|pos| words.bits[pos / 64] |= 1 << (pos % 64));
+        words
+    }
+
+    // Returns `true` if both sets use the same index, so they can be
+    // combined bit by bit. Indexes holding the same words but built
+    // separately don't count; comparing them costs more than the
+    // slow path does.
+
+    pub(crate) fn shares_index(&self, words: &Words) -> bool {
+        Arc::ptr_eq(&self.index, &words.index)
+    }
+
+    // Returns an empty set using the same index.
+
+    pub(crate) fn empty(&self) -> Words {
+        Words {
+            index: self.index.clone(),
+            bits: vec![0; self.bits.len()],
+        }
+    }
+
+    // Returns the position of `word` in the set's index, whether or
+    // not it's in the set.
+
+    pub(crate) fn position(&self, word: &str) -> Option<usize> {
+        self.index.position(word)
+    }
+
+    // Returns the index positions of the words in the set.
+
+    pub(crate) fn positions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits
+            .iter()
+            .enumerate()
+            .flat_map(|(ii, block)| Bits(*block).map(move |bit| ii * 64 + bit))
+    }
+
+    // Keeps the words whose index positions pass the test.
+
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(usize) -> bool) {
+        for (ii, block) in self.bits.iter_mut().enumerate() {
+            for bit in Bits(*block) {
+                if !keep(ii * 64 + bit) {
+                    *block &= !(1 << bit)
+                }
+            }
+        }
+    }
+
+    // Keeps the words that pass the test. The set keeps its index.
+
+    pub(crate) fn retain_words(
+        &mut self, mut keep: impl FnMut(&'static str) -> bool,
+    ) {
+        let index = self.index.clone();
+
+        self.retain(|pos| keep(index.0[pos]))
     }
 
     // Returns the number of words in the set.
 
     pub fn total(&self) -> usize {
-        self.0.len()
+        self.bits.iter().map(|e| e.count_ones() as usize).sum()
     }
 
-    // Tries to determine the best words to choose from from a set of
-    // words. First, a subset of the input is made consisting of the
-    // "best" candidates. From this set, a random one is chosen. If
-    // the "best" set is empty, then we choose a randon one from the
-    // original set.
+    // Returns `true` if the word is in the set.
 
-    pub fn pick_word(&self) -> &'static str {
-        let remaining_words = self.0.len();
-        let best: Set<&'static str> = self
-            .0
-            .iter()
-            .filter(|word| {
-                word.find(&['a', 'e', 'i', 'o', 'u', 'y']) != None
-                    && word.find("uo") == None
-                    && word.find("ln") == None
-                    && word.find("kh") == None
-                    && word.find("eu") == None
-                    && (
-                        remaining_words <= 100
-                            || word
-                            .chars()
-                            .sorted()
-                            .dedup()
-                            .filter(|c| {
-                                *c != 'z'
-                                    && *c != 'q'
-                                    && *c != 'v'
-                                    && *c != 'x'
-                                    && *c != 'j'
-                            })
-                            .count()
-                            == 5
-                    )
-            })
-            .cloned()
-            .collect();
-
-        if best.is_empty() {
-            let choice = rand::random::<usize>() % self.0.len();
-
-            self.0
-                .iter()
-                .enumerate()
-                .find(|(idx, _)| *idx == choice)
-                .unwrap()
-                .1
-        } else {
-            let choice = rand::random::<usize>() % best.len();
+    pub fn contains(&self, word: &str) -> bool {
+        self.index
+            .position(word)
+            .is_some_and(|pos| self.bits[pos / 64] & (1 << (pos % 64)) != 0)
+    }
 
-            best.iter()
-                .enumerate()
-                .find(|(idx, _)| *idx == choice)
-                .unwrap()
-                .1
+    // Returns `true` if every word in the set is also in `words`.
+
+    pub(crate) fn is_subset(&self, words: &Words) -> bool {
+        if self.shares_index(words) {
+            self.bits.iter().zip(&words.bits).all(|(a, b)| a & !b == 0)
+        } else {
+            self.iter().all(|e| words.contains(e))
         }
     }
 
-    // Consumes the set of words and returns a possibly smaller set of
-    // words. The returned set contains words that were in both sets.
+    // Returns the set's copy of `word`, if it's in the set. It lives
+    // as long as the program, like the words of the lists.
+
+    pub fn find(&self, word: &str) -> Option<&'static str> {
+        self.index
+            .position(word)
+            .filter(|pos| self.bits[pos / 64] & (1 << (pos % 64)) != 0)
+            .map(|pos| self.index.0[pos])
+    }
+
+    // Returns an iterator over the words in the set, in alphabetical
+    // order.
+
+    pub fn iter(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.positions().map(|pos| self.index.0[pos])
+    }
+
+    // Reduces the set to the words that are also in the passed set.
 
     pub fn preserve(&mut self, words: &Words) {
-        self.0 = self
-            .0
-            .intersection(&words.0)
-            .cloned()
-            .collect::<Set<&'static str>>();
+        if self.shares_index(words) {
+            self.bits
+                .iter_mut()
+                .zip(&words.bits)
+                .for_each(|(a, b)| *a &= b)
+        } else {
+            let keep: Vec<usize> = self
+                .iter()
+                .filter(|e| words.contains(e))
+                .filter_map(|e| self.index.position(e))
+                .collect();
+
+            *self = Words::with_index(&self.index, keep.into_iter())
+        }
     }
 
-    // Consumes the set of words and returns a possibly smaller set of
-    // words. The returned set doesn't have any of the words that are
-    // also in the passed set.
+    // Removes the words that are also in the passed set.
 
     pub fn remove(&mut self, words: &Words) {
-        self.0 = self
-            .0
-            .difference(&words.0)
-            .cloned()
-            .collect::<Set<&'static str>>();
+        if self.shares_index(words) {
+            self.bits
+                .iter_mut()
+                .zip(&words.bits)
+                .for_each(|(a, b)| *a &= !b)
+        } else {
+            for pos in words.iter().filter_map(|e| self.index.position(e)) {
+                self.bits[pos / 64] &= !(1 << (pos % 64))
+            }
+        }
     }
 
-    // Consumes the set of words and returns a possibly larger set of
-    // words. The returned set has all the words in both sets.
+    // Adds the words in the passed set. If some of them aren't in
+    // this set's index, the set gets a new index holding both sets'
+    // words.
 
     pub fn union(&mut self, words: &Words) {
-        self.0 = self
-            .0
-            .union(&words.0)
-            .cloned()
-            .collect::<Set<&'static str>>();
+        if self.shares_index(words) {
+            self.bits
+                .iter_mut()
+                .zip(&words.bits)
+                .for_each(|(a, b)| *a |= b)
+        } else if words.iter().all(|e| self.index.position(e).is_some()) {
+            for pos in words.iter().filter_map(|e| self.index.position(e)) {
+                self.bits[pos / 64] |= 1 << (pos % 64)
+            }
+        } else {
+            let all: Vec<&'static str> =
+                self.iter().chain(words.iter()).collect();
+
+            *self = Words::new(&all)
+        }
+    }
+}
+
+impl PartialEq for Words {
+    fn eq(&self, other: &Words) -> bool {
+        if self.shares_index(other) {
+            self.bits == other.bits
+        } else {
+            self.iter().eq(other.iter())
+        }
+    }
+}
+
+impl Eq for Words {}
+
+impl fmt::Debug for Words {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Words(")?;
+        f.debug_set().entries(self.iter()).finish()?;
+        write!(f, ")")
     }
 }
 
@@ -1752,29 +1905,37 @@ pub type GreenKey = (usize, char);
 pub struct GreenTable(Map<GreenKey, Words>);
 
 impl GreenTable {
-    fn new_from_slice(s: &'static [&'static str]) -> GreenTable {
-        let mut tbl: Map<GreenKey, Words> = Map::new();
+    // Returns a new table with all the words in `s` properly
+    // inserted.
 
-        for word in s {
-            for key in word.char_indices() {
-                if let Some(set) = tbl.get_mut(&key) {
-                    set.0.insert(word);
-                } else {
-                    let mut set = Words::new(&[]);
+    pub fn new_from_slice(s: &[&'static str]) -> GreenTable {
+        GreenTable::with_index(&Index::new(s), s)
+    }
 
-                    set.0.insert(word);
-                    tbl.insert(key, set);
-                }
-            }
-        }
-        GreenTable(tbl)
+    // Returns a table of the words in `words`, using the same index so
+    // sets taken from `words` are reduced quickly.
+
+    pub(crate) fn for_words(words: &Words) -> GreenTable {
+        GreenTable::with_index(&words.index, &words.iter().collect::<Vec<_>>())
     }
 
-    // Returns a new table, pre-stuffed with all the words in `WORDS`
-    // properly inserted.
+    // Returns a table of the words in `s`, whose sets use `index`.
 
-    pub fn new() -> GreenTable {
-        GreenTable::new_from_slice(WORDS)
+    fn with_index(index: &Arc<Index>, s: &[&'static str]) -> GreenTable {
+        let mut tbl: Map<GreenKey, Vec<usize>> = Map::new();
+
+        for word in s {
+            let pos = index.position(word).unwrap();
+
+            for key in word.char_indices() {
+                tbl.entry(key).or_default().push(pos)
+            }
+        }
+        GreenTable(
+            tbl.into_iter()
+                .map(|(k, v)| (k, Words::with_index(index, v.into_iter())))
+                .collect(),
+        )
     }
 
     // Performs a look-up in the table.
@@ -1790,59 +1951,433 @@ pub type CharFreqKey = (usize, char);
 pub struct CharFreqTable(Map<CharFreqKey, Words>);
 
 impl CharFreqTable {
-    pub fn new_from_slice(s: &'static [&'static str]) -> CharFreqTable {
-        let mut tbl: Map<CharFreqKey, Words> = Map::new();
+    // Returns a new table with all the words in `s` properly
+    // inserted.
+
+    pub fn new_from_slice(s: &[&'static str]) -> CharFreqTable {
+        CharFreqTable::with_index(&Index::new(s), s)
+    }
+
+    // Returns a table of the words in `words`, using the same index.
+
+    pub(crate) fn for_words(words: &Words) -> CharFreqTable {
+        CharFreqTable::with_index(
+            &words.index,
+            &words.iter().collect::<Vec<_>>(),
+        )
+    }
+
+    // Returns a table of the words in `s`, whose sets use `index`.
+
+    fn with_index(index: &Arc<Index>, s: &[&'static str]) -> CharFreqTable {
+        let mut tbl: Map<CharFreqKey, Vec<usize>> = Map::new();
 
         for word in s {
+            let pos = index.position(word).unwrap();
+
             for key in word.chars().sorted().dedup_with_count() {
-                if let Some(set) = tbl.get_mut(&key) {
-                    set.0.insert(word);
-                } else {
-                    let mut set = Words::new(&[]);
+                tbl.entry(key).or_default().push(pos)
+            }
+        }
+        CharFreqTable(
+            tbl.into_iter()
+                .map(|(k, v)| (k, Words::with_index(index, v.into_iter())))
+                .collect(),
+        )
+    }
 
-                    set.0.insert(word);
-                    tbl.insert(key, set);
-                }
+    // Performs a look-up in the table.
+
+    pub fn get(&self, key: &CharFreqKey) -> Option<&Words> {
+        self.0.get(key)
+    }
+}
+
+// Returns a copy of `word` that lives as long as the program. The
+// sets and tables don't own their words; they hold `&'static str`
+// values, so the words of a loaded list are leaked to get them. Each
+// distinct word is only leaked once, however many lists hold it, so
+// loading a list again doesn't use more memory.
+
+pub(crate) fn intern(word: &str) -> &'static str {
+    static WORDS: Mutex<Set<&'static str>> = Mutex::new(Set::new());
+
+    let mut words = WORDS.lock().unwrap();
+
+    match words.get(word) {
+        Some(word) => word,
+        None => {
+            let word: &'static str = Box::leak(word.into());
+
+            words.insert(word);
+            word
+        }
+    }
+}
+
+// Holds the word lists used for a session: the words that can be the
+// answer and the other words that are accepted as guesses. The words
+// of lists loaded from files come from `intern()`, so the rest of the
+// program treats them like the built-in lists.
+
+pub struct WordLists {
+    answers: Vec<&'static str>,
+    guesses: Vec<&'static str>,
+
+    // The index of both lists. Every set and table made from the
+    // lists uses it.
+    index: Arc<Index>,
+}
+
+impl WordLists {
+    // Returns the built-in word lists.
+
+    pub fn new() -> WordLists {
+        WordLists::with_lists(ANSWERS.to_vec(), GUESSES.to_vec())
+    }
+
+    pub(crate) fn with_lists(
+        answers: Vec<&'static str>, guesses: Vec<&'static str>,
+    ) -> WordLists {
+        WordLists {
+            index: Index::new(&[answers.as_slice(), &guesses].concat()),
+            answers,
+            guesses,
+        }
+    }
+
+    // Returns the word lists to use for a session. A list that isn't
+    // given a file uses the built-in list. Both lists must hold words
+    // of the same length. If a file of answers has words that aren't
+    // five letters long, and no file of guesses is given, the
+    // built-in guesses can't be used so only the answers can be
+    // guessed.
+
+    pub fn load(
+        answers: Option<&Path>, guesses: Option<&Path>,
+    ) -> io::Result<WordLists> {
+        let mut lists = WordLists::new();
+
+        if let Some(path) = answers {
+            lists.answers = read_list(path)?;
+
+            if lists.answers[0].len() != GUESSES[0].len() {
+                lists.guesses = vec![]
+            }
+        }
+
+        if let Some(path) = guesses {
+            lists.guesses = read_list(path)?;
+
+            if lists.guesses[0].len() != lists.word_len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{}: the guesses have {} letters but the answers have {}",
+                        path.display(),
+                        lists.guesses[0].len(),
+                        lists.word_len()
+                    ),
+                ));
             }
         }
-        CharFreqTable(tbl)
+        Ok(WordLists::with_lists(lists.answers, lists.guesses))
     }
 
-    // Returns a new table, pre-stuffed with all the words in `WORDS`
-    // properly inserted.
+    // Removes words from both lists. This is how words Wordle won't
+    // accept are kept from being guessed. It's an error if it
+    // leaves no answers.
+
+    pub fn exclude(&mut self, words: &[String]) -> io::Result<()> {
+        let keep = |e: &&'static str| !words.iter().any(|w| w == e);
+
+        self.answers.retain(keep);
+        self.guesses.retain(keep);
+        self.index =
+            Index::new(&[self.answers.as_slice(), &self.guesses].concat());
 
-    pub fn new() -> CharFreqTable {
-        CharFreqTable::new_from_slice(WORDS)
+        if self.answers.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "every answer has been excluded",
+            ));
+        }
+        Ok(())
     }
 
-    // Performs a look-up in the table.
+    // Returns the number of letters in each word.
 
-    pub fn get(&self, key: &CharFreqKey) -> Option<&Words> {
-        self.0.get(key)
+    pub fn word_len(&self) -> usize {
+        self.answers[0].len()
+    }
+
+    // Returns a set containing all the possible answers. This is
+    // `webster`'s initial vocabulary. Hints only ever narrow it down.
+
+    pub fn vocabulary(&self) -> Words {
+        Words::with_index(
+            &self.index,
+            self.answers.iter().filter_map(|e| self.index.position(e)),
+        )
+    }
+
+    // Returns a set containing every word that may be played as a
+    // guess: all the answers along with the other allowed guesses.
+
+    pub fn guesses(&self) -> Words {
+        Words::with_index(&self.index, 0..self.index.0.len())
+    }
+
+    // Returns the tables used to apply hints to the vocabulary.
+
+    pub fn green_table(&self) -> GreenTable {
+        GreenTable::with_index(&self.index, &self.answers)
+    }
+
+    pub fn freq_table(&self) -> CharFreqTable {
+        CharFreqTable::with_index(&self.index, &self.answers)
     }
 }
 
-// Returns a set containing all the words in `WORDS`. This is
-// `webster`'s initial vocabulary.
+impl Default for WordLists {
+    fn default() -> Self {
+        WordLists::new()
+    }
+}
 
-pub fn get_vocabulary() -> Words {
-    let mut vocab: Set<&'static str> = Set::new();
+// Reads the list of words Wordle has rejected. It has the same
+// format as a word list but, since it's built up as words get
+// rejected, it isn't validated and a missing file is the same as an
+// empty one.
 
-    for word in WORDS {
-        vocab.insert(word);
+pub fn read_blacklist(path: &Path) -> io::Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text
+            .lines()
+            .map(|e| e.trim().to_ascii_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(io::Error::new(
+            e.kind(),
+            format!("{}: {}", path.display(), e),
+        )),
     }
-    Words(vocab)
 }
 
-#[cfg(test)]
-pub fn mk_green_tbl(s: &'static [&'static str]) -> GreenTable {
-    GreenTable::new_from_slice(s)
+// Adds a word to the list of words Wordle has rejected, creating the
+// file if it doesn't exist.
+
+pub fn add_to_blacklist(path: &Path, word: &str) -> io::Result<()> {
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", word))
+        .map_err(|e| {
+            io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+        })
+}
+
+// Reads a word list from a file.
+
+fn read_list(path: &Path) -> io::Result<Vec<&'static str>> {
+    let text = fs::read_to_string(path).map_err(|e| {
+        io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+    })?;
+
+    parse_list(&path.display().to_string(), &text)
+}
+
+// Parses a word list. There's one word per line; surrounding
+// whitespace and blank lines are ignored. Each word is checked to
+// make sure it only has letters, has the same length as the first
+// word and wasn't seen earlier in the list. Errors report the name of
+// the list and the line number of the bad word.
+
+fn parse_list(name: &str, text: &str) -> io::Result<Vec<&'static str>> {
+    let mut seen: Map<String, usize> = Map::new();
+    let mut words = Vec::new();
+
+    for (line, word) in text.lines().enumerate().map(|(n, e)| (n + 1, e.trim()))
+    {
+        let error = |msg: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: {}", name, line, msg),
+            )
+        };
+
+        if word.is_empty() {
+            continue;
+        }
+
+        if !word.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(error(format!(
+                "\"{}\" may only contain the letters A through Z",
+                word
+            )));
+        }
+
+        let len = words.first().map_or(word.len(), |e: &&str| e.len());
+
+        if word.len() > MAX_LEN {
+            return Err(error(format!(
+                "\"{}\" has {} letters; words can't have more than {}",
+                word,
+                word.len(),
+                MAX_LEN
+            )));
+        }
+
+        if word.len() != len {
+            return Err(error(format!(
+                "\"{}\" has {} letters; the words before it have {}",
+                word,
+                word.len(),
+                len
+            )));
+        }
+
+        let word = word.to_ascii_lowercase();
+
+        if let Some(first) = seen.get(&word) {
+            return Err(error(format!(
+                "\"{}\" is a duplicate of line {}",
+                word, first
+            )));
+        }
+
+        seen.insert(word.clone(), line);
+        words.push(intern(&word));
+    }
+
+    if words.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: the list doesn't contain any words", name),
+        ));
+    }
+    Ok(words)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_word_lists() {
+        let lists = WordLists::new();
+        let vocab = lists.vocabulary();
+        let guesses = lists.guesses();
+
+        assert_eq!(vocab.total(), ANSWERS.len());
+        assert_eq!(guesses.total(), ANSWERS.len() + GUESSES.len());
+
+        // Every answer must be an allowed guess and the two lists
+        // shouldn't overlap.
+
+        assert!(vocab.iter().all(|e| guesses.contains(e)));
+        assert!(GUESSES.iter().all(|e| !vocab.contains(e)));
+    }
+
+    #[test]
+    fn test_blacklist() {
+        let path = std::env::temp_dir()
+            .join(format!("wordlebot-blacklist-{}", std::process::id()));
+
+        let _ = fs::remove_file(&path);
+        assert!(read_blacklist(&path).unwrap().is_empty());
+
+        add_to_blacklist(&path, "camel").unwrap();
+        add_to_blacklist(&path, "aahed").unwrap();
+
+        let words = read_blacklist(&path).unwrap();
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(words, vec!["camel", "aahed"]);
+
+        let mut lists = WordLists::new();
+
+        lists.exclude(&words).unwrap();
+        assert!(!lists.vocabulary().contains("camel"));
+        assert!(!lists.guesses().contains("camel"));
+        assert!(!lists.guesses().contains("aahed"));
+        assert_eq!(lists.guesses().total(), ANSWERS.len() + GUESSES.len() - 2);
+
+        let mut lists = WordLists::with_lists(vec!["camel"], vec![]);
+
+        assert!(lists.exclude(&words).is_err());
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            parse_list("test", "camel\n\n  OTTER \ntatty\n").unwrap(),
+            vec!["camel", "otter", "tatty"]
+        );
+
+        let error = |text| parse_list("test", text).unwrap_err().to_string();
+
+        assert_eq!(
+            error("camel\nott3r\n"),
+            "test:2: \"ott3r\" may only contain the letters A through Z"
+        );
+        assert_eq!(
+            error("camel\notters\n"),
+            "test:2: \"otters\" has 6 letters; the words before it have 5"
+        );
+        assert_eq!(
+            error("abcdefghijk\n"),
+            "test:1: \"abcdefghijk\" has 11 letters; words can't have more than 10"
+        );
+        assert_eq!(
+            error("camel\notter\n\nCamel\n"),
+            "test:4: \"camel\" is a duplicate of line 1"
+        );
+        assert_eq!(error("\n  \n"), "test: the list doesn't contain any words");
+
+        // Loading a list again reuses the words of the first load.
+
+        let first = parse_list("test", "camel\notter\n").unwrap();
+        let second = parse_list("test", "OTTER\ncamel\n").unwrap();
+
+        assert!(std::ptr::eq(first[0], second[1]));
+        assert!(std::ptr::eq(first[1], second[0]));
+    }
+
+    #[test]
+    fn test_shared_index() {
+        let lists = WordLists::with_lists(
+            vec!["camel", "hazel", "otter"],
+            vec!["aahed", "zymic"],
+        );
+        let gt = lists.green_table();
+        let mut vocab = lists.vocabulary();
+
+        assert_eq!(
+            vocab.iter().collect::<Vec<_>>(),
+            ["camel", "hazel", "otter"]
+        );
+
+        vocab.preserve(gt.get(&(4, 'l')).unwrap());
+        assert_eq!(vocab, Words::new(&["camel", "hazel"]));
+
+        let mut guesses = lists.guesses();
+
+        guesses.remove(&vocab);
+        assert_eq!(guesses, Words::new(&["aahed", "otter", "zymic"]));
+
+        guesses.union(gt.get(&(0, 'c')).unwrap());
+        assert_eq!(guesses.total(), 4);
+        assert!(guesses.contains("camel") && !guesses.contains("hazel"));
+
+        // Words from another index are added to a new one.
+
+        vocab.union(&Words::new(&["abcde"]));
+        assert_eq!(vocab, Words::new(&["abcde", "camel", "hazel"]));
+    }
+
     #[test]
     fn test_union() {
         let mut a = Words::new(&["abcd"]);
@@ -1851,6 +2386,21 @@ mod tests {
 
         a.union(&b);
         assert_eq!(a, c);
+
+        // A set keeps its index, even when it's empty, as long as the
+        // words it gets are in the index.
+
+        let lists = WordLists::with_lists(vec!["camel", "otter"], vec![]);
+        let mut a = lists.vocabulary().empty();
+
+        a.union(&Words::new(&["otter"]));
+        assert!(a.shares_index(&lists.guesses()));
+        assert_eq!(a, Words::new(&["otter"]));
+
+        // Indexes built separately aren't shared, even if they hold
+        // the same words.
+
+        assert!(!Words::new(&["otter"]).shares_index(&Words::new(&["otter"])));
     }
 
     #[test]
@@ -1886,6 +2436,23 @@ mod tests {
         assert_eq!(a, c);
     }
 
+    #[test]
+    fn test_is_subset() {
+        let lists = WordLists::with_lists(vec!["abcd", "efgh", "ijkl"], vec![]);
+        let all = lists.vocabulary();
+        let mut some = all.clone();
+
+        some.retain_words(|e| e != "ijkl");
+        assert!(some.is_subset(&all));
+        assert!(!all.is_subset(&some));
+        assert!(lists.vocabulary().empty().is_subset(&some));
+
+        // Sets with separate indexes compare their words.
+
+        assert!(Words::new(&["efgh"]).is_subset(&some));
+        assert!(!Words::new(&["ijkl"]).is_subset(&some));
+    }
+
     #[test]
     fn test_green_table() {
         let mut expected: GreenTable = GreenTable(Map::new());
@@ -1930,15 +2497,4 @@ mod tests {
 
         assert_eq!(tbl, expected);
     }
-
-    #[test]
-    fn test_pick_words() {
-        const ANSWER: &str = "infer";
-        let words = Words::new(&[
-            ANSWER, "crwth", "fluor", "pizza", "quiet", "exact", "jelly",
-            "petty", "swoln", "rikha",
-        ]);
-
-        assert_eq!(words.pick_word(), ANSWER);
-    }
 }
@@ -0,0 +1,259 @@
+use crate::Hint;
+
+// Computes the hints Wordle would give for `guess` if the secret word
+// was `answer`. This follows the rules documented in the README:
+// greens are assigned first and then any remaining instances of a
+// letter are handled left to right, with yellows assigned before
+// blacks.
+//
+// This is the one place that knows how feedback is generated. Code
+// that needs to validate clues or simulate games should call it
+// rather than reproduce the rules. Both words are expected to be
+// lowercase ASCII letters and have the same length.
+
+pub fn score(guess: &str, answer: &str) -> Vec<Hint> {
+    let mut hints = vec![Hint::Black; guess.len()];
+    let mut unused = [0usize; 26];
+
+    // Assign the green hints. Any letter of the answer that didn't
+    // get matched is counted so it can be handed out as a yellow.
+
+    for (idx, (g, a)) in guess.bytes().zip(answer.bytes()).enumerate() {
+        if g == a {
+            hints[idx] = Hint::Green
+        } else {
+            unused[(a - b'a') as usize] += 1
+        }
+    }
+
+    // Now walk the guess left to right and hand out yellows while
+    // unmatched instances of the letter remain.
+
+    for (idx, g) in guess.bytes().enumerate() {
+        let count = &mut unused[(g - b'a') as usize];
+
+        if hints[idx] != Hint::Green && *count > 0 {
+            *count -= 1;
+            hints[idx] = Hint::Yellow
+        }
+    }
+    hints
+}
+
+// Returns the hints for `guess` against `answer` encoded as a single
+// number. Each hint is a base-3 digit (black is 0, yellow is 1 and
+// green is 2) with the first position being the least significant.
+// This is handy for sorting words into buckets by the hints they
+// would produce. Words can't be longer than `dictionary::MAX_LEN`
+// letters or the result won't fit.
+
+pub fn pattern(guess: &str, answer: &str) -> u16 {
+    encode(&score(guess, answer))
+}
+
+// Encodes hints the way `pattern()` does.
+
+pub fn encode(hints: &[Hint]) -> u16 {
+    hints.iter().rev().fold(0, |acc, hint| {
+        acc * 3
+            + match hint {
+                Hint::Black => 0,
+                Hint::Yellow => 1,
+                Hint::Green => 2,
+            }
+    })
+}
+
+// Returns the number of distinct values `pattern()` can return for
+// words of the given length.
+
+pub fn patterns(len: usize) -> usize {
+    3usize.pow(len as u32)
+}
+
+// Returns `true` if hard mode allows `word` to be played after
+// `guess` received `hints`. Hard mode requires every green letter to
+// be reused in the same position and every yellow letter to be used
+// somewhere. A letter that got more than one green or yellow must
+// appear at least that many times.
+
+pub fn hard_mode_allows(word: &str, guess: &str, hints: &[Hint]) -> bool {
+    let mut needed = [0usize; 26];
+
+    for ((w, g), hint) in word.bytes().zip(guess.bytes()).zip(hints) {
+        match hint {
+            Hint::Green if w != g => return false,
+            Hint::Green | Hint::Yellow => needed[(g - b'a') as usize] += 1,
+            Hint::Black => (),
+        }
+    }
+
+    for w in word.bytes() {
+        let count = &mut needed[(w - b'a') as usize];
+
+        *count = count.saturating_sub(1)
+    }
+    needed.iter().all(|e| *e == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Hint::{Black as B, Green as G, Yellow as Y};
+
+    #[test]
+    fn test_no_duplicates() {
+        assert_eq!(score("camel", "camel"), [G, G, G, G, G]);
+        assert_eq!(score("fghij", "abcde"), [B, B, B, B, B]);
+        assert_eq!(score("irate", "camel"), [B, B, Y, B, Y]);
+        assert_eq!(score("edcba", "abcde"), [Y, Y, G, Y, Y]);
+        assert_eq!(score("named", "camel"), [B, G, G, G, B]);
+    }
+
+    #[test]
+    fn test_duplicates_in_guess() {
+        // The example from the README.
+
+        assert_eq!(score("tatty", "otter"), [Y, B, G, B, B]);
+
+        // The answer has one L. The green takes it, so the other L
+        // is black even though it comes first.
+
+        assert_eq!(score("hello", "world"), [B, B, B, G, Y]);
+        assert_eq!(score("geese", "those"), [B, B, B, G, G]);
+        assert_eq!(score("eerie", "crate"), [B, B, Y, B, G]);
+
+        // No greens for the letter, so the leftmost instance gets the
+        // yellow.
+
+        assert_eq!(score("llama", "camel"), [Y, B, Y, Y, B]);
+        assert_eq!(score("speed", "abide"), [B, B, Y, B, Y]);
+        assert_eq!(score("sassy", "class"), [Y, Y, B, G, B]);
+        assert_eq!(score("mamma", "maxim"), [G, G, Y, B, B]);
+    }
+
+    #[test]
+    fn test_duplicates_in_answer() {
+        // The guess has one instance of a letter the answer has
+        // twice; only one clue can be given for it.
+
+        assert_eq!(score("crane", "geese"), [B, B, B, B, G]);
+        assert_eq!(score("stone", "geese"), [Y, B, B, B, G]);
+        assert_eq!(score("plant", "allay"), [B, G, Y, B, B]);
+        assert_eq!(score("adieu", "llama"), [Y, B, B, B, B]);
+    }
+
+    #[test]
+    fn test_duplicates_in_both() {
+        assert_eq!(score("tatty", "tatty"), [G, G, G, G, G]);
+        assert_eq!(score("otter", "tatty"), [B, Y, G, B, B]);
+        assert_eq!(score("sissy", "mossy"), [B, B, G, G, G]);
+        assert_eq!(score("mossy", "sissy"), [B, B, G, G, G]);
+        assert_eq!(score("eerie", "geese"), [Y, G, B, B, G]);
+        assert_eq!(score("geese", "eerie"), [B, G, Y, B, G]);
+        assert_eq!(score("array", "rarer"), [Y, Y, G, B, B]);
+        assert_eq!(score("abbey", "babes"), [Y, Y, G, G, B]);
+    }
+
+    #[test]
+    fn test_pattern() {
+        assert_eq!(pattern("fghij", "abcde"), 0);
+        assert_eq!(pattern("camel", "camel"), 242);
+        assert_eq!(pattern("tatty", "otter"), 1 + 2 * 9);
+        assert_eq!(pattern("bfghi", "abcde"), 1);
+        assert_eq!(pattern("fghib", "abcde"), 81);
+    }
+
+    #[test]
+    fn test_hard_mode_allows() {
+        let hints = score("tatty", "otter");
+
+        assert!(hard_mode_allows("otter", "tatty", &hints));
+        assert!(hard_mode_allows("tithe", "tatty", &hints));
+        assert!(!hard_mode_allows("hotel", "tatty", &hints));
+        assert!(!hard_mode_allows("plate", "tatty", &hints));
+
+        // Two yellows for the same letter require it twice.
+
+        let hints = score("array", "rarer");
+
+        assert!(hard_mode_allows("rarer", "array", &hints));
+        assert!(hard_mode_allows("rural", "array", &hints));
+        assert!(!hard_mode_allows("parka", "array", &hints));
+        assert!(hard_mode_allows("abcde", "fghij", &[B, B, B, B, B]));
+    }
+
+    #[test]
+    fn test_other_lengths() {
+        assert_eq!(score("tatt", "otto"), [Y, B, G, B]);
+        assert_eq!(score("letter", "settle"), [Y, G, G, G, Y, B]);
+        assert_eq!(score("abcdefg", "abcdefg"), [G; 7]);
+        assert_eq!(pattern("abcdefg", "abcdefg") as usize, patterns(7) - 1);
+        assert_eq!(patterns(4), 81);
+    }
+
+    // The rules uniquely determine the hints, so rather than compare
+    // against another implementation, check every guess/answer pair
+    // built from a small alphabet against the properties the hints
+    // must satisfy:
+    //
+    // - A position is green if and only if the letters match.
+    // - For each letter, the number of green and yellow hints is
+    //   the smaller of its counts in the guess and the answer.
+    // - Yellows go to the leftmost non-green instances.
+
+    fn check(guess: &str, answer: &str) {
+        let hints = score(guess, answer);
+        let g: Vec<char> = guess.chars().collect();
+        let a: Vec<char> = answer.chars().collect();
+
+        for idx in 0..5 {
+            assert_eq!(hints[idx] == G, g[idx] == a[idx], "{guess}/{answer}");
+        }
+
+        for ch in ['a', 'b', 'c'] {
+            let in_guess = g.iter().filter(|c| **c == ch).count();
+            let in_answer = a.iter().filter(|c| **c == ch).count();
+            let marked = (0..5)
+                .filter(|idx| g[*idx] == ch && hints[*idx] != B)
+                .count();
+
+            assert_eq!(marked, in_guess.min(in_answer), "{guess}/{answer}");
+
+            // Once a non-green instance is black, every later
+            // non-green instance must also be black.
+
+            let mut seen_black = false;
+
+            for idx in (0..5).filter(|idx| g[*idx] == ch && hints[*idx] != G) {
+                if hints[idx] == B {
+                    seen_black = true
+                } else {
+                    assert!(!seen_black, "{guess}/{answer}")
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_exhaustive_small_alphabet() {
+        let words: Vec<String> = (0..243)
+            .map(|mut n| {
+                (0..5)
+                    .map(|_| {
+                        let ch = (b'a' + n % 3) as char;
+
+                        n /= 3;
+                        ch
+                    })
+                    .collect()
+            })
+            .collect();
+
+        for guess in &words {
+            for answer in &words {
+                check(guess, answer)
+            }
+        }
+    }
+}
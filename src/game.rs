@@ -0,0 +1,455 @@
+use crate::analysis::{self, TurnReport};
+use crate::conflict::{self, Conflict};
+use crate::dictionary::{CharFreqTable, GreenTable, WordLists, Words};
+use crate::matrix::{self, Matrix};
+use crate::strategy::{self, Strategy, Turn};
+use crate::{to_hint_string, Hint};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::sync::Arc;
+use std::{fmt, io};
+
+// The reasons a game can't do what it was asked.
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    // No word fits the clues, which usually means one was entered
+    // wrong. `Game::conflict()` explains which.
+    NoWords,
+
+    // Every word that fits the clues was skipped. The skipped words
+    // are forgotten so the next guess starts over.
+    AllSkipped,
+
+    // The game is over; the last guess was the answer.
+    Solved,
+
+    // The word isn't made of the right number of lowercase letters.
+    BadWord(String),
+
+    // There isn't a hint for every letter of the guess.
+    BadHints { expected: usize, found: usize },
+
+    // There aren't that many turns to go back.
+    BadTurn(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NoWords => write!(f, "no word fits the clues"),
+            Error::AllSkipped => write!(f, "every word that fits was skipped"),
+            Error::Solved => write!(f, "the game is already solved"),
+            Error::BadWord(word) => {
+                write!(f, "\"{}\" isn't a word that can be guessed", word)
+            }
+            Error::BadHints { expected, found } => {
+                write!(f, "expected {} hints but there are {}", expected, found)
+            }
+            Error::BadTurn(n) => write!(f, "can't go back {} turn(s)", n),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+}
+
+// The state of one game: the clues so far and the words that still
+// fit them. The game asks its strategy for each guess and the caller
+// applies the hints Wordle gave for it.
+
+pub struct Game {
+    // Every word that could be the answer, before any clues. It's
+    // needed to explain conflicting clues and to analyze the game.
+    answers: Words,
+    guesses: Words,
+    vocab: Words,
+    gt: GreenTable,
+    ft: CharFreqTable,
+    matrix: Option<Arc<Matrix>>,
+    len: usize,
+
+    strategy: Box<dyn Strategy>,
+    hard: bool,
+    rng: StdRng,
+
+    history: Vec<(String, Vec<Hint>)>,
+
+    // The vocabulary at the start of each turn in `history`. It's
+    // what gets restored when going back to fix a clue.
+    vocabs: Vec<Words>,
+
+    // The words Wordle wouldn't accept this turn.
+    skipped: Words,
+}
+
+impl Game {
+    // Starts a game using the words in `lists`. Games with the same
+    // seed and the same clues make the same guesses.
+
+    pub fn new(
+        lists: &WordLists, strategy: Box<dyn Strategy>, seed: u64,
+    ) -> Game {
+        Game {
+            answers: lists.vocabulary(),
+            guesses: lists.guesses(),
+            vocab: lists.vocabulary(),
+            gt: lists.green_table(),
+            ft: lists.freq_table(),
+            matrix: None,
+            len: lists.word_len(),
+            strategy,
+            hard: false,
+            rng: StdRng::seed_from_u64(seed),
+            history: Vec::with_capacity(6),
+            vocabs: Vec::with_capacity(6),
+            skipped: lists.guesses().empty(),
+        }
+    }
+
+    // Plays the game by hard mode rules. If `explain` is set, the
+    // report of each guess says when hard mode changed it.
+
+    pub fn hard(self, explain: bool) -> Game {
+        Game {
+            strategy: Box::new(strategy::Hard::new(self.strategy, explain)),
+            hard: true,
+            ..self
+        }
+    }
+
+    // Applies hints using a pattern matrix made from the same word
+    // lists.
+
+    pub fn with_matrix(self, matrix: Arc<Matrix>) -> Game {
+        Game {
+            matrix: Some(matrix),
+            ..self
+        }
+    }
+
+    pub fn word_len(&self) -> usize {
+        self.len
+    }
+
+    // The words that could still be the answer.
+
+    pub fn candidates(&self) -> &Words {
+        &self.vocab
+    }
+
+    pub fn guesses(&self) -> &Words {
+        &self.guesses
+    }
+
+    pub fn history(&self) -> &[(String, Vec<Hint>)] {
+        &self.history
+    }
+
+    // The number of the turn being played, starting at 1.
+
+    pub fn turn(&self) -> usize {
+        self.history.len() + 1
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.history
+            .last()
+            .is_some_and(|(_, hints)| hints.iter().all(|e| *e == Hint::Green))
+    }
+
+    // Asks the strategy for the next guess. Skipped words are hidden
+    // from it so it picks something else.
+
+    pub fn next_guess(&mut self) -> Result<&'static str, Error> {
+        if self.is_solved() {
+            return Err(Error::Solved);
+        }
+        if self.vocab.total() == 0 {
+            return Err(Error::NoWords);
+        }
+
+        let mut candidates = self.vocab.clone();
+        let mut guesses = self.guesses.clone();
+
+        candidates.remove(&self.skipped);
+        guesses.remove(&self.skipped);
+
+        if candidates.total() == 0 {
+            self.skipped = self.guesses.empty();
+            return Err(Error::AllSkipped);
+        }
+
+        Ok(self.strategy.pick(
+            &Turn {
+                candidates: &candidates,
+                guesses: &guesses,
+                history: &self.history,
+                matrix: self.matrix.as_deref(),
+            },
+            &mut self.rng,
+        ))
+    }
+
+    // The strategy's description of how it chose the last guess.
+
+    pub fn report(&self) -> Option<String> {
+        self.strategy.report()
+    }
+
+    // Applies the hints Wordle gave for `guess`, which doesn't have
+    // to be the guess the game suggested.
+
+    pub fn apply(&mut self, guess: &str, hints: &[Hint]) -> Result<(), Error> {
+        if self.is_solved() {
+            return Err(Error::Solved);
+        }
+        if guess.len() != self.len
+            || !guess.chars().all(|c| c.is_ascii_lowercase())
+        {
+            return Err(Error::BadWord(guess.to_string()));
+        }
+        if hints.len() != self.len {
+            return Err(Error::BadHints {
+                expected: self.len,
+                found: hints.len(),
+            });
+        }
+
+        self.skipped = self.guesses.empty();
+        self.vocabs.push(self.vocab.clone());
+        self.history.push((guess.to_string(), hints.to_vec()));
+
+        // A solved game leaves one word, the answer.
+
+        self.vocab = if self.is_solved() {
+            let pos = self.vocab.position(guess);
+            let mut vocab = self.vocab.clone();
+
+            vocab.retain(|e| Some(e) == pos);
+            vocab
+        } else {
+            matrix::process_hints(
+                self.matrix.as_deref(),
+                self.vocab.clone(),
+                &self.gt,
+                &self.ft,
+                guess,
+                hints,
+            )
+        };
+        Ok(())
+    }
+
+    // Goes back `n` turns, forgetting their clues. The guess made at
+    // that turn is returned so its hints can be entered again.
+
+    pub fn back(&mut self, n: usize) -> Result<String, Error> {
+        if n == 0 || n > self.history.len() {
+            return Err(Error::BadTurn(n));
+        }
+
+        let turn = self.history.len() - n;
+        let guess = self.history[turn].0.clone();
+
+        self.vocab = self.vocabs[turn].clone();
+        self.skipped = self.guesses.empty();
+        self.history.truncate(turn);
+        self.vocabs.truncate(turn);
+        Ok(guess)
+    }
+
+    // Wordle didn't accept `word`. It won't be guessed again this
+    // turn. Only the words in the lists can be guessed, so any other
+    // word is ignored.
+
+    pub fn skip(&mut self, word: &str) {
+        if let Some(word) = self.guesses.find(word) {
+            self.skipped.union(&Words::new(&[word]))
+        }
+    }
+
+    // Wordle doesn't know `word`. It's forgotten for the rest of the
+    // game, including in the turns the game can go back to.
+
+    pub fn reject(&mut self, word: &str) {
+        let word = match self.guesses.find(word) {
+            Some(word) => Words::new(&[word]),
+            None => return,
+        };
+
+        self.vocab.remove(&word);
+        self.answers.remove(&word);
+        self.guesses.remove(&word);
+        self.vocabs.iter_mut().for_each(|e| e.remove(&word));
+    }
+
+    // Explains why no word fits the clues.
+
+    pub fn conflict(&self) -> Conflict {
+        conflict::explain(&self.history, &self.answers, self.matrix.as_deref())
+    }
+
+    // Returns the `n` guesses expected to tell the most, along with
+    // the bits each is expected to provide and whether it could be
+    // the answer. In hard mode, only the guesses hard mode allows are
+    // considered.
+
+    pub fn top(&self, n: usize) -> Vec<(&'static str, f64, bool)> {
+        let turn = Turn {
+            candidates: &self.vocab,
+            guesses: &self.guesses,
+            history: &self.history,
+            matrix: self.matrix.as_deref(),
+        };
+        let legal = if self.hard {
+            strategy::legal_guesses(&turn)
+        } else {
+            self.guesses.clone()
+        };
+
+        strategy::rank(
+            &Turn {
+                guesses: &legal,
+                ..turn
+            },
+            n,
+        )
+        .into_iter()
+        .map(|(word, bits)| (word, bits, self.vocab.contains(word)))
+        .collect()
+    }
+
+    // Reports on each turn played so far. `strategy` suggests what
+    // it would have played instead.
+
+    pub fn analyze(
+        &self, strategy: Box<dyn Strategy>, rng: &mut dyn RngCore,
+    ) -> io::Result<Vec<TurnReport>> {
+        let tables = analysis::Tables {
+            vocab: &self.answers,
+            guesses: &self.guesses,
+            gt: &self.gt,
+            ft: &self.ft,
+            matrix: self.matrix.as_deref(),
+        };
+
+        analysis::analyze(&self.history, &tables, strategy, rng, self.hard)
+    }
+}
+
+impl fmt::Debug for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let history: Vec<String> = self
+            .history
+            .iter()
+            .map(|(guess, hints)| {
+                format!("{} {}", guess, to_hint_string(hints))
+            })
+            .collect();
+
+        f.debug_struct("Game")
+            .field("history", &history)
+            .field("candidates", &self.vocab.total())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback;
+    use Hint::{Black as B, Green as G};
+
+    fn game() -> Game {
+        Game::new(&WordLists::new(), strategy::build("heuristic").unwrap(), 0)
+    }
+
+    #[test]
+    fn test_play() {
+        let mut game = game();
+
+        while !game.is_solved() {
+            let guess = game.next_guess().unwrap();
+
+            game.apply(guess, &feedback::score(guess, "otter")).unwrap();
+            assert!(game.candidates().contains("otter"));
+            assert!(game.turn() <= 10);
+        }
+
+        assert_eq!(game.history().last().unwrap().0, "otter");
+        assert_eq!(game.next_guess(), Err(Error::Solved));
+        assert_eq!(game.apply("otter", &[G; 5]), Err(Error::Solved));
+    }
+
+    #[test]
+    fn test_same_seed() {
+        let (mut g1, mut g2) = (game(), game());
+
+        assert_eq!(g1.next_guess(), g2.next_guess());
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut game = game();
+        let total = game.candidates().total();
+
+        assert_eq!(
+            game.apply("otters", &[B; 6]),
+            Err(Error::BadWord("otters".to_string()))
+        );
+        assert_eq!(
+            game.apply("otter", &[B; 4]),
+            Err(Error::BadHints {
+                expected: 5,
+                found: 4
+            })
+        );
+
+        game.apply("otter", &[B, B, B, G, B]).unwrap();
+        assert!(game.candidates().total() < total);
+        assert!(game.candidates().iter().all(|e| !e.contains('t')));
+
+        // Going back restores the vocabulary.
+
+        assert_eq!(game.back(2), Err(Error::BadTurn(2)));
+        assert_eq!(game.back(1), Ok("otter".to_string()));
+        assert_eq!(game.candidates().total(), total);
+        assert_eq!(game.turn(), 1);
+    }
+
+    #[test]
+    fn test_no_words() {
+        let mut game = game();
+
+        game.apply("zzzzz", &[G, G, G, G, B]).unwrap();
+        assert_eq!(game.next_guess(), Err(Error::NoWords));
+        assert_eq!(game.conflict().turns, vec![0]);
+    }
+
+    #[test]
+    fn test_skip_and_reject() {
+        let mut game = game();
+
+        game.apply("shore", &feedback::score("shore", "store"))
+            .unwrap();
+
+        let words: Vec<&str> = game.candidates().iter().collect();
+
+        for word in &words {
+            game.skip(word)
+        }
+        assert_eq!(game.next_guess(), Err(Error::AllSkipped));
+        assert!(game.next_guess().is_ok());
+
+        for word in &words {
+            game.reject(word)
+        }
+        assert_eq!(game.next_guess(), Err(Error::NoWords));
+        assert!(!game.guesses().contains(words[0]));
+    }
+}
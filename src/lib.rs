@@ -0,0 +1,529 @@
+// The engine behind wordlebot: word lists, scoring, strategies and
+// the state of a game. The `wordlebot` program is built on top of it
+// and other tools can use it too; `Game` is the place to start.
+
+use clap::ArgEnum;
+use std::collections::*;
+
+// Define general names for sets and maps. I thought it might be
+// interesting, once the program is working, to test the Hash versions
+// against the BTree versions. This lets us change the types of
+// containers in this one location.
+//
+// Sets of words ended up as bitsets (see `dictionary::Words`);
+// `benches/words.rs` compares them against both kinds of set.
+
+type Set<T> = BTreeSet<T>;
+type Map<K, V> = BTreeMap<K, V>;
+
+pub mod analysis;
+pub mod conflict;
+pub mod dictionary;
+pub mod feedback;
+mod game;
+pub mod matrix;
+pub mod strategy;
+pub mod tree;
+
+pub use game::{Error, Game};
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum, Debug)]
+pub enum Theme {
+    Normal,
+    HighContrast,
+}
+
+#[derive(Copy, PartialEq, Clone, Debug)]
+pub enum Hint {
+    Black,
+    Yellow,
+    Green,
+}
+
+impl Hint {
+    pub fn to_char(self, theme: &Theme) -> char {
+        match (self, theme) {
+            (Hint::Black, _) => '⬛',
+            (Hint::Yellow, Theme::Normal) => '🟨',
+            (Hint::Green, Theme::Normal) => '🟩',
+            (Hint::Yellow, Theme::HighContrast) => '🟦',
+            (Hint::Green, Theme::HighContrast) => '🟧',
+        }
+    }
+}
+
+impl TryFrom<char> for Hint {
+    type Error = ();
+
+    fn try_from(ch: char) -> Result<Hint, ()> {
+        match ch {
+            'G' | 'g' => Ok(Hint::Green),
+            'Y' | 'y' => Ok(Hint::Yellow),
+            'B' | 'b' => Ok(Hint::Black),
+            _ => Err(()),
+        }
+    }
+}
+
+// Holds character frequency information. This type is meant to be fed
+// a stream of Hints; the first is fed to `new()` and the rest to
+// `update()`. The value will keep track of how many of the character
+// could be in the word, based on the hints.
+
+#[derive(Debug, PartialEq)]
+struct FreqInfo(usize, usize);
+
+impl FreqInfo {
+    // Create a new `FreqInfo` with an initial hint. `len` is the
+    // length of the words, which is the most times a character could
+    // appear.
+
+    pub fn new(hint: &Hint, len: usize) -> FreqInfo {
+        if *hint == Hint::Black {
+            FreqInfo(0, 0)
+        } else {
+            FreqInfo(1, len)
+        }
+    }
+
+    // Updates the possible range of totals of the character based on
+    // the hint passed to it. Note the upper limit will be
+    // conservatively high because each `FreqInfo` has no access to
+    // other frequency measurements.
+
+    pub fn update(&mut self, hint: &Hint) {
+        match hint {
+            Hint::Black => self.1 = self.0,
+            Hint::Yellow | Hint::Green => {
+                self.0 += 1;
+                self.1 = std::cmp::max(self.0, self.1)
+            }
+        }
+    }
+}
+
+pub fn to_hint_string(hints: &[Hint]) -> String {
+    hints
+        .iter()
+        .map(|e| match e {
+            Hint::Black => 'B',
+            Hint::Yellow => 'Y',
+            Hint::Green => 'G',
+        })
+        .collect()
+}
+
+// Uses the green and yellow hints to reduce the vocabulary. For a
+// green hint, the GreenTable is used to find all words with the
+// character in the position. The vocabulary is ANDed (i.e. the
+// intersection) with the set of words which will remove words that
+// don't have that condition. For yellow hints, we remove all words
+// with the character in the position from the vocabulary. This
+// preserves words with the character -- just not in the position.
+
+fn process_position_hints(
+    vocab: &mut dictionary::Words, gt: &dictionary::GreenTable, guess: &str,
+    hints: &[Hint],
+) {
+    // Turn the guess and hints into a (idx, hint, guess char)
+    // iterator.
+
+    let iter = hints
+        .iter()
+        .enumerate()
+        .zip(guess.chars())
+        .map(|((idx, hint), ch)| (idx, hint, ch));
+
+    let empty = dictionary::Words::new(&[]);
+
+    // Loop through the hint/guess items and process each.
+
+    for (idx, hint, ch) in iter {
+        // This algorithm doesn't handle Black hints.
+
+        if *hint != Hint::Black {
+            // The guess may not be a possible answer, so it's possible
+            // no word in the table has the character in this
+            // position.
+
+            let words = gt.get(&(idx, ch)).unwrap_or(&empty);
+
+            // If it was a Green hint, compute the intersection of the
+            // vocabulary with the set of words having the character
+            // in the current position.
+
+            if *hint == Hint::Green {
+                vocab.preserve(words)
+            } else {
+                // It's a Yellow hint. Build up a set of words that
+                // have the current character in every position *but*
+                // the current one.
+
+                let mut keep_words = dictionary::Words::new(&[]);
+
+                for ii in 0..guess.len() {
+                    if ii != idx {
+                        if let Some(tmp) = gt.get(&(ii, ch)) {
+                            keep_words.union(tmp);
+                        }
+                    }
+                }
+
+                // Compute the intersection of the vocabulary with the
+                // words containing the current character *not* in the
+                // current position.
+
+                if keep_words.total() > 0 {
+                    vocab.preserve(&keep_words);
+                }
+
+                // Remove all words from the vocabulary where the
+                // current character is in the current position.
+
+                vocab.remove(words)
+            }
+        }
+    }
+}
+
+fn bld_freq_info_table(hints: &[Hint], guess: &str) -> Map<char, FreqInfo> {
+    let mut freq = Map::<char, FreqInfo>::new();
+    let len = hints.len();
+
+    // Build the table of char -> freq info.
+
+    for (hint, ch) in hints.iter().zip(guess.chars()) {
+        if let Some(info) = freq.get_mut(&ch) {
+            info.update(hint);
+        } else {
+            freq.insert(ch, FreqInfo::new(hint, len));
+        }
+    }
+
+    // Make one more pass through the table and adjust the upper bound
+    // of each entry.
+
+    let maxes: Vec<(char, usize)> = freq
+        .iter()
+        .filter(|(_, v)| v.0 > 0)
+        .map(|(k, v)| (*k, v.0))
+        .collect();
+
+    for (ii_k, ii_v) in freq.iter_mut() {
+        let mut total = 0;
+
+        for (jj_k, jj_v) in &maxes {
+            if jj_k != ii_k {
+                total += jj_v
+            }
+        }
+
+        ii_v.1 = std::cmp::min(ii_v.1, len - total)
+    }
+
+    freq
+}
+
+// Use the clues to reduce the vocabulary.
+
+pub fn process_hints(
+    mut vocab: dictionary::Words, gt: &dictionary::GreenTable,
+    ft: &dictionary::CharFreqTable, guess: &str, hints: &[Hint],
+) -> dictionary::Words {
+    process_position_hints(&mut vocab, gt, guess, hints);
+
+    let freq = bld_freq_info_table(hints, guess);
+    let mut keep_words = dictionary::Words::new(&[]);
+
+    for (ch, FreqInfo(l, h)) in freq.iter() {
+        for ii in 1..=hints.len() {
+            if let Some(tmp) = ft.get(&(ii, *ch)) {
+                if ii < *l || ii > *h {
+                    vocab.remove(tmp);
+                } else {
+                    keep_words.union(tmp);
+                }
+            }
+        }
+    }
+
+    if keep_words.total() > 0 {
+        vocab.preserve(&keep_words);
+    }
+
+    vocab
+}
+
+pub fn to_lossy_string(guess: &[Hint], theme: &Theme) -> String {
+    guess.iter().map(|e| e.to_char(theme)).collect::<String>()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn apply_state(hints: &[Hint]) -> FreqInfo {
+        let mut info = FreqInfo::new(&hints[0], 5);
+
+        for hint in &hints[1..] {
+            info.update(hint)
+        }
+        info
+    }
+
+    #[test]
+    fn test_freq_info() {
+        assert_eq!(apply_state(&[Hint::Black]), FreqInfo(0, 0));
+        assert_eq!(apply_state(&[Hint::Yellow]), FreqInfo(1, 5));
+        assert_eq!(apply_state(&[Hint::Green]), FreqInfo(1, 5));
+
+        assert_eq!(apply_state(&[Hint::Black, Hint::Black]), FreqInfo(0, 0));
+        assert_eq!(apply_state(&[Hint::Black, Hint::Yellow]), FreqInfo(1, 1));
+        assert_eq!(apply_state(&[Hint::Black, Hint::Green]), FreqInfo(1, 1));
+
+        assert_eq!(apply_state(&[Hint::Yellow, Hint::Black]), FreqInfo(1, 1));
+        assert_eq!(apply_state(&[Hint::Yellow, Hint::Yellow]), FreqInfo(2, 5));
+        assert_eq!(apply_state(&[Hint::Yellow, Hint::Green]), FreqInfo(2, 5));
+
+        assert_eq!(apply_state(&[Hint::Green, Hint::Black]), FreqInfo(1, 1));
+        assert_eq!(apply_state(&[Hint::Green, Hint::Yellow]), FreqInfo(2, 5));
+        assert_eq!(apply_state(&[Hint::Green, Hint::Green]), FreqInfo(2, 5));
+
+        assert_eq!(
+            apply_state(&[Hint::Black, Hint::Black, Hint::Black]),
+            FreqInfo(0, 0)
+        );
+        assert_eq!(
+            apply_state(&[Hint::Black, Hint::Yellow, Hint::Black]),
+            FreqInfo(1, 1)
+        );
+        assert_eq!(
+            apply_state(&[Hint::Black, Hint::Green, Hint::Black]),
+            FreqInfo(1, 1)
+        );
+
+        assert_eq!(
+            apply_state(&[Hint::Green, Hint::Yellow, Hint::Black]),
+            FreqInfo(2, 2)
+        );
+    }
+
+    #[test]
+    fn test_freq_info_table() {
+        {
+            let tbl = bld_freq_info_table(
+                &[
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                ],
+                "abcde",
+            );
+            let mut expected: Map<char, FreqInfo> = Map::new();
+
+            expected.insert('a', FreqInfo(0, 0));
+            expected.insert('b', FreqInfo(0, 0));
+            expected.insert('c', FreqInfo(0, 0));
+            expected.insert('d', FreqInfo(0, 0));
+            expected.insert('e', FreqInfo(0, 0));
+
+            assert_eq!(tbl, expected);
+        }
+        {
+            let tbl = bld_freq_info_table(
+                &[
+                    Hint::Yellow,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                ],
+                "abcde",
+            );
+            let mut expected: Map<char, FreqInfo> = Map::new();
+
+            expected.insert('a', FreqInfo(1, 5));
+            expected.insert('b', FreqInfo(0, 0));
+            expected.insert('c', FreqInfo(0, 0));
+            expected.insert('d', FreqInfo(0, 0));
+            expected.insert('e', FreqInfo(0, 0));
+
+            assert_eq!(tbl, expected);
+        }
+        {
+            let tbl = bld_freq_info_table(
+                &[
+                    Hint::Yellow,
+                    Hint::Yellow,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                ],
+                "aabcd",
+            );
+            let mut expected: Map<char, FreqInfo> = Map::new();
+
+            expected.insert('a', FreqInfo(2, 5));
+            expected.insert('b', FreqInfo(0, 0));
+            expected.insert('c', FreqInfo(0, 0));
+            expected.insert('d', FreqInfo(0, 0));
+
+            assert_eq!(tbl, expected);
+        }
+        {
+            let tbl = bld_freq_info_table(
+                &[
+                    Hint::Yellow,
+                    Hint::Yellow,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                ],
+                "aaabc",
+            );
+            let mut expected: Map<char, FreqInfo> = Map::new();
+
+            expected.insert('a', FreqInfo(2, 2));
+            expected.insert('b', FreqInfo(0, 0));
+            expected.insert('c', FreqInfo(0, 0));
+
+            assert_eq!(tbl, expected);
+        }
+        {
+            let tbl = bld_freq_info_table(
+                &[
+                    Hint::Yellow,
+                    Hint::Yellow,
+                    Hint::Black,
+                    Hint::Yellow,
+                    Hint::Black,
+                ],
+                "aaabc",
+            );
+            let mut expected: Map<char, FreqInfo> = Map::new();
+
+            expected.insert('a', FreqInfo(2, 2));
+            expected.insert('b', FreqInfo(1, 3));
+            expected.insert('c', FreqInfo(0, 0));
+
+            assert_eq!(tbl, expected);
+        }
+        {
+            let tbl = bld_freq_info_table(
+                &[
+                    Hint::Yellow,
+                    Hint::Yellow,
+                    Hint::Black,
+                    Hint::Yellow,
+                    Hint::Black,
+                ],
+                "aacbd",
+            );
+            let mut expected: Map<char, FreqInfo> = Map::new();
+
+            expected.insert('a', FreqInfo(2, 4));
+            expected.insert('b', FreqInfo(1, 3));
+            expected.insert('c', FreqInfo(0, 0));
+            expected.insert('d', FreqInfo(0, 0));
+
+            assert_eq!(tbl, expected);
+        }
+    }
+
+    #[test]
+    fn test_position_hints() {
+        {
+            let mut vocab =
+                dictionary::Words::new(&["aaaaa", "bbbba", "cccac"]);
+            let gt = dictionary::GreenTable::new_from_slice(&[
+                "aaaaa", "bbbba", "cccac",
+            ]);
+            let expected = dictionary::Words::new(&["aaaaa", "bbbba"]);
+
+            process_position_hints(
+                &mut vocab,
+                &gt,
+                "aaaaa",
+                &[
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Green,
+                ],
+            );
+            assert_eq!(vocab, expected);
+        }
+
+        {
+            let mut vocab =
+                dictionary::Words::new(&["aaaaa", "bbbba", "cccac"]);
+            let gt = dictionary::GreenTable::new_from_slice(&[
+                "aaaaa", "bbbba", "cccac",
+            ]);
+            let expected = dictionary::Words::new(&["cccac"]);
+
+            process_position_hints(
+                &mut vocab,
+                &gt,
+                "aaaaa",
+                &[
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Yellow,
+                ],
+            );
+            assert_eq!(vocab, expected);
+        }
+
+        {
+            let mut vocab =
+                dictionary::Words::new(&["aaaaa", "aacab", "bbbba", "cccac"]);
+            let gt = dictionary::GreenTable::new_from_slice(&[
+                "aaaaa", "aacab", "bbbba", "cccac",
+            ]);
+            let expected = dictionary::Words::new(&["aacab"]);
+
+            process_position_hints(
+                &mut vocab,
+                &gt,
+                "aaaac",
+                &[
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Green,
+                    Hint::Yellow,
+                ],
+            );
+            assert_eq!(vocab, expected);
+        }
+
+        // The guess doesn't have to be in the table. A green for a
+        // character no word has in that position leaves nothing.
+
+        {
+            let mut vocab = dictionary::Words::new(&["aaaaa", "bbbba"]);
+            let gt =
+                dictionary::GreenTable::new_from_slice(&["aaaaa", "bbbba"]);
+
+            process_position_hints(
+                &mut vocab,
+                &gt,
+                "zzzzz",
+                &[
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Black,
+                    Hint::Green,
+                ],
+            );
+            assert_eq!(vocab, dictionary::Words::new(&[]));
+        }
+    }
+}
@@ -1,54 +1,18 @@
-use clap::{ArgEnum, Parser};
-use std::collections::*;
-use std::io::{self, Write};
-
-// Define general names for sets and maps. I thought it might be
-// interesting, once the program is working, to test the Hash versions
-// against the BTree versions. This lets us change the types of
-// containers in this one location.
-
-type Set<T> = BTreeSet<T>;
-type Map<K, V> = BTreeMap<K, V>;
-
-mod dictionary;
-
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum, Debug)]
-enum Theme {
-    Normal,
-    HighContrast,
-}
-
-#[derive(PartialEq, Clone, Debug)]
-enum Hint {
-    Black,
-    Yellow,
-    Green,
-}
-
-impl Hint {
-    pub fn to_char(&self, theme: &Theme) -> char {
-        match (self, theme) {
-            (Hint::Black, _) => '⬛',
-            (Hint::Yellow, Theme::Normal) => '🟨',
-            (Hint::Green, Theme::Normal) => '🟩',
-            (Hint::Yellow, Theme::HighContrast) => '🟦',
-            (Hint::Green, Theme::HighContrast) => '🟧',
-        }
-    }
-}
-
-impl TryFrom<char> for Hint {
-    type Error = ();
-
-    fn try_from(ch: char) -> Result<Hint, ()> {
-        match ch {
-            'G' | 'g' => Ok(Hint::Green),
-            'Y' | 'y' => Ok(Hint::Yellow),
-            'B' | 'b' => Ok(Hint::Black),
-            _ => Err(()),
-        }
-    }
-}
+use clap::{Parser, Subcommand};
+use cli::input::Input;
+use cli::{analysis, bench, share, tree};
+use rand::{rngs::StdRng, SeedableRng};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use wordlebot::matrix::Matrix;
+use wordlebot::strategy::{self, Strategy};
+use wordlebot::tree::Tree;
+use wordlebot::{dictionary, feedback};
+use wordlebot::{Error, Game, Hint, Theme};
+
+mod cli;
+mod protocol;
 
 #[derive(Parser, Debug)]
 #[clap(name = "Webster")]
@@ -76,551 +40,446 @@ struct Args {
     #[clap(
         long,
         default_value_t = 20,
-	env = "WORDLEBOT_LIMIT",
+        env = "WORDLEBOT_LIMIT",
         help = "Set vocabulary report limit",
         long_help = "This sets the limit which decides whether the number of words remaining is reported instead of each word."
     )]
     limit: usize,
-}
 
-// Holds character frequency information. This type is meant to be fed
-// a stream of Hints; the first is fed to `new()` and the rest to
-// `update()`. The value will keep track of how many of the character
-// could be in the word, based on the hints.
+    #[clap(
+        long,
+        value_name = "WORD",
+        conflicts_with = "answer-stdin",
+        help = "Play against a known word",
+        long_help = "Instead of asking for hints, wordlebot generates them itself by comparing each guess against this word. This is useful for watching wordlebot play without having to enter clues by hand."
+    )]
+    answer: Option<String>,
 
-#[derive(Debug, PartialEq)]
-struct FreqInfo(usize, usize);
+    #[clap(
+        long,
+        help = "Read the known word from stdin",
+        long_help = "Like --answer, but the word is read from the first line of stdin so it doesn't show up in the shell history or the process list."
+    )]
+    answer_stdin: bool,
 
-impl FreqInfo {
-    // Create a new `FreqInfo` with an initial hint.
+    #[clap(
+        long,
+        conflicts_with_all = &["answer", "answer-stdin"],
+        help = "Suggest guesses instead of making them",
+        long_help = "For playing Wordle yourself. Enter each word you played along with its hints (e.g. \"crane bygbb\") and wordlebot shows the words that could still be the answer and the word it would guess next. Entering only the hints accepts the suggested word."
+    )]
+    assist: bool,
 
-    pub fn new(hint: &Hint) -> FreqInfo {
-        if *hint == Hint::Black {
-            FreqInfo(0, 0)
-        } else {
-            FreqInfo(1, 5)
-        }
-    }
+    #[clap(
+        long,
+        help = "Analyze the game once it's solved",
+        long_help = "Once the word is guessed, reports for each turn how many words were left before and after the hints, how much information the guess was expected to give compared to the best guess, how lucky the hints were and what wordlebot would have played instead."
+    )]
+    analyze: bool,
 
-    // Updates the possible range of totals of the character based on
-    // the hint passed to it. Note the upper limit will be
-    // conservatively high because each `FreqInfo` has no access to
-    // other frequency measurements.
-
-    pub fn update(&mut self, hint: &Hint) {
-        match hint {
-            Hint::Black => self.1 = self.0,
-            Hint::Yellow | Hint::Green => {
-                self.0 += 1;
-                self.1 = std::cmp::max(self.0, self.1)
-            }
-        }
-    }
-}
+    #[clap(
+        short,
+        long,
+        global = true,
+        default_value = strategy::DEFAULT,
+        value_parser = clap::builder::PossibleValuesParser::new(strategy::possible_values()),
+        help = "Choose how guesses are picked",
+        long_help = "Chooses the strategy used to pick each guess. The entropy strategy can take a few seconds to pick its first guess. The lookahead strategy takes about half a minute on a single CPU; more threads or --cache cut that down."
+    )]
+    strategy: String,
 
-// Returns hints given by the user. The loop is so the input can be
-// re-entered if the user entered something invalid.
+    #[clap(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = strategy::Settings::default().lookahead_width,
+        help = "Number of guesses the lookahead strategy tries",
+        long_help = "How many guesses the lookahead strategy tries at each step, taken from the ones expected to tell the most. Larger values can find better guesses but take longer."
+    )]
+    lookahead_width: usize,
 
-fn get_hints() -> io::Result<String> {
-    loop {
-        let mut input = String::new();
+    #[clap(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = strategy::Settings::default().lookahead_depth,
+        help = "Number of guesses the lookahead strategy looks ahead",
+        long_help = "How many guesses deep the lookahead strategy plays out each guess it tries, counting the guess itself. Past that, the guesses needed are estimated. Each step deeper multiplies the work by about the width."
+    )]
+    lookahead_depth: usize,
 
-        // Prompt the user and get the hints.
+    #[clap(
+        long,
+        global = true,
+        env = "WORDLEBOT_SEED",
+        help = "Seed the random number generator",
+        long_help = "Seeds the random number generator used when picking words. Using the seed of an earlier session, along with the same clues, reproduces its guesses exactly. If not given, a random seed is used; verbose mode reports it."
+    )]
+    seed: Option<u64>,
 
-        print!("   Hints> ");
-        io::stdout().flush()?;
+    #[clap(
+        long,
+        global = true,
+        value_name = "FILE",
+        env = "WORDLEBOT_ANSWERS",
+        help = "Load the possible answers from a file",
+        long_help = "Loads the words that can be the answer from a file instead of using the built-in list. The file should have one word per line."
+    )]
+    answers: Option<PathBuf>,
 
-        io::stdin().read_line(&mut input)?;
+    #[clap(
+        long,
+        global = true,
+        value_name = "FILE",
+        env = "WORDLEBOT_GUESSES",
+        help = "Load the allowed guesses from a file",
+        long_help = "Loads the words, besides the answers, that may be played as guesses from a file instead of using the built-in list. The file should have one word per line."
+    )]
+    guesses: Option<PathBuf>,
 
-        // Remove trailing whitespace and make everything uppercase so
-        // we don't have to test for lowercase hints.
+    #[clap(
+        long,
+        global = true,
+        value_name = "FILE",
+        env = "WORDLEBOT_BLACKLIST",
+        help = "Never guess the words in a file",
+        long_help = "Words in this file are removed from the word lists at startup. When a guess is rejected at the hints prompt, it's added to the file so it's never guessed again. The file is created if it doesn't exist."
+    )]
+    blacklist: Option<PathBuf>,
 
-        let input = input.trim().to_uppercase();
+    #[clap(
+        long,
+        global = true,
+        value_name = "DIR",
+        env = "WORDLEBOT_CACHE",
+        help = "Keep the pattern matrix in a directory",
+        long_help = "The pattern matrix holds the hints every guess gets against every answer. With this option, the strategies look hints up in it instead of working them out, and it's used to apply hints, which is faster and more precise. It takes a moment to compute so it's saved in this directory and reused as long as the word lists don't change."
+    )]
+    cache: Option<PathBuf>,
 
-        // The input *must* be 5 characters.
+    #[clap(
+        long,
+        global = true,
+        value_name = "FILE",
+        env = "WORDLEBOT_TREE",
+        help = "Play the guesses of a solving tree",
+        long_help = "Looks up each guess in a solving tree, like the ones `tree build` writes, instead of working it out. Once a game leaves the tree, the strategy picks the guesses."
+    )]
+    tree: Option<PathBuf>,
 
-        if input.len() != 5 {
-            println!("ERROR: hints must contain 5 characters");
-            continue;
-        }
+    #[clap(
+        long,
+        global = true,
+        env = "WORDLEBOT_THREADS",
+        help = "Set the number of threads used to score guesses",
+        long_help = "The strategies that score every allowed guess (entropy, minimax and lookahead), `top` and the pattern matrix split the work across this many threads. If not given, or 0, there's one per CPU. Turns with few words left are scored on one thread anyway. The guesses don't depend on the number of threads, so a seed reproduces a game with any number of them."
+    )]
+    threads: Option<usize>,
 
-        // The input can only contain the letters B, Y, and G.
+    #[clap(
+        long,
+        global = true,
+        help = "Play by hard mode rules",
+        long_help = "Only guesses that reuse every clue revealed so far are played: green letters stay in place and yellow letters are included. In verbose mode, wordlebot reports when hard mode kept it from playing the guess it would otherwise have picked."
+    )]
+    hard: bool,
 
-        if input.matches(|c| c == 'B' || c == 'Y' || c == 'G').count() < 5 {
-            println!("ERROR: only letters in hints are B, Y, and G");
-            continue;
-        }
+    #[clap(
+        long,
+        arg_enum,
+        default_value_t = protocol::Protocol::Text,
+        help = "Choose how to talk to wordlebot",
+        long_help = "The text protocol is meant for people. The JSON protocol is meant for programs: wordlebot writes one JSON object per line describing each guess and reads one JSON object per line holding the hints or a command. See the README for the details."
+    )]
+    protocol: protocol::Protocol,
 
-        return Ok(input);
-    }
+    #[clap(subcommand)]
+    command: Option<Command>,
 }
 
-// Uses the green and yellow hints to reduce the vocabulary. For a
-// green hint, the GreenTable is used to find all words with the
-// character in the position. The vocabulary is ANDed (i.e. the
-// intersection) with the set of words which will remove words that
-// don't have that condition. For yellow hints, we remove all words
-// with the character in the position from the vocabulary. This
-// preserves words with the character -- just not in the position.
-
-fn process_position_hints(
-    vocab: &mut dictionary::Words, gt: &dictionary::GreenTable, guess: &str,
-    hints: &[Hint],
-) {
-    // Turn the guess and hints into a (idx, hint, guess char)
-    // iterator.
-
-    let iter = hints
-        .iter()
-        .enumerate()
-        .zip(guess.chars())
-        .map(|((idx, hint), ch)| (idx, hint, ch));
-
-    // Loop through the hint/guess items and process each.
-
-    for (idx, hint, ch) in iter {
-        // This algorithm doesn't handle Black hints.
-
-        if *hint != Hint::Black {
-            let words = gt.get(&(idx, ch)).unwrap();
-
-            // If it was a Green hint, compute the intersection of the
-            // vocabulary with the set of words having the character
-            // in the current position.
-
-            if *hint == Hint::Green {
-                vocab.preserve(words)
-            } else {
-                // It's a Yellow hint. Build up a set of words that
-                // have the current character in every position *but*
-                // the current one.
-
-                let mut keep_words = dictionary::Words::new(&[]);
-
-                for ii in 0..=4 {
-                    if ii != idx {
-                        if let Some(tmp) = gt.get(&(ii, ch)) {
-                            keep_words.union(tmp);
-                        }
-                    }
-                }
-
-                // Compute the intersection of the vocabulary with the
-                // words containing the current character *not* in the
-                // current position.
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[clap(
+        about = "Measure how well wordlebot plays",
+        long_about = "Plays a game against every word in the vocabulary and reports the number of guesses it took to solve them."
+    )]
+    Bench(bench::Options),
 
-                if keep_words.total() > 0 {
-                    vocab.preserve(&keep_words);
-                }
+    #[clap(
+        about = "Analyze a game",
+        long_about = "Reports, for each turn of a game, how many words the guess ruled out, how that compares to the best guess and what wordlebot would have played instead."
+    )]
+    Analyze(analysis::Options),
 
-                // Remove all words from the vocabulary where the
-                // current character is in the current position.
+    #[clap(
+        about = "Read the hints from share text",
+        long_about = "Reads the text Wordle shares once a puzzle is done and prints the hints of each row. Given the answer, it also lists the words that could have been guessed for each row."
+    )]
+    Share(share::Options),
 
-                vocab.remove(words)
-            }
-        }
-    }
+    #[clap(
+        about = "Work with solving trees",
+        long_about = "A solving tree holds the guess to play after every set of hints, starting from an opener, so games can be played by looking up moves."
+    )]
+    Tree(tree::Options),
 }
 
-fn bld_freq_info_table(hints: &[Hint], guess: &str) -> Map<char, FreqInfo> {
-    let mut freq = Map::<char, FreqInfo>::new();
+// Returns the secret word, if the user asked wordlebot to play
+// against one. The word is validated against the vocabulary because
+// wordlebot could never guess a word it doesn't know.
 
-    // Build the table of char -> freq info.
+fn get_answer(
+    arg: &Args, vocab: &dictionary::Words,
+) -> io::Result<Option<String>> {
+    let answer = if arg.answer_stdin {
+        let mut input = String::new();
 
-    for (hint, ch) in hints.iter().zip(guess.chars()) {
-        if let Some(info) = freq.get_mut(&ch) {
-            info.update(hint);
-        } else {
-            freq.insert(ch, FreqInfo::new(hint));
-        }
+        io::stdin().read_line(&mut input)?;
+        input
+    } else if let Some(answer) = &arg.answer {
+        answer.clone()
+    } else {
+        return Ok(None);
+    };
+
+    let answer = answer.trim().to_lowercase();
+
+    if !vocab.contains(&answer) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("\"{}\" isn't in my vocabulary", answer.to_uppercase()),
+        ));
     }
+    Ok(Some(answer))
+}
 
-    // Make one more pass through the table and adjust the upper bound
-    // of each entry.
-
-    let maxes: Vec<(char, usize)> = freq
-        .iter()
-        .filter(|(_, v)| v.0 > 0)
-        .map(|(k, v)| (*k, v.0))
-        .collect();
-
-    for (ii_k, ii_v) in freq.iter_mut() {
-        let mut total = 0;
+// Loads the word lists and runs the subcommand, if there is one.
+// Otherwise it enters the main loop of the program where it gets a
+// guess from the game, waits for clues, then applies them.
 
-        for (jj_k, jj_v) in &maxes {
-            if jj_k != ii_k {
-                total += jj_v
-            }
-        }
-
-        ii_v.1 = std::cmp::min(ii_v.1, 5 - total)
+fn run(arg: &Args) -> io::Result<()> {
+    if let Some(threads) = arg.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(io::Error::other)?
     }
 
-    freq
-}
-
-// Use the clues to reduce the vocabulary.
-
-fn process_hints(
-    mut vocab: dictionary::Words, gt: &dictionary::GreenTable,
-    ft: &dictionary::CharFreqTable, guess: &str, hints: &[Hint],
-) -> dictionary::Words {
-    process_position_hints(&mut vocab, gt, guess, hints);
+    let mut lists = dictionary::WordLists::load(
+        arg.answers.as_deref(),
+        arg.guesses.as_deref(),
+    )?;
 
-    let freq = bld_freq_info_table(hints, guess);
-    let mut keep_words = dictionary::Words::new(&[]);
+    if let Some(path) = &arg.blacklist {
+        lists.exclude(&dictionary::read_blacklist(path)?)?
+    }
 
-    for (ch, FreqInfo(l, h)) in freq.iter() {
-        for ii in 1..=5 {
-            if let Some(tmp) = ft.get(&(ii, *ch)) {
-                if ii < *l || ii > *h {
-                    vocab.remove(tmp);
-                } else {
-                    keep_words.union(tmp);
-                }
+    let seed = arg.seed.unwrap_or_else(rand::random);
+    let matrix = match &arg.cache {
+        Some(dir) => Some(Arc::new(Matrix::load(&lists, dir)?)),
+        None => None,
+    };
+    let tree = match &arg.tree {
+        Some(path) => Some(Arc::new(Tree::read(path, &lists.guesses())?)),
+        None => None,
+    };
+
+    // Builds the strategy chosen on the command line. It's built more
+    // than once when a game is analyzed.
+
+    let build = || -> Box<dyn Strategy> {
+        let settings = strategy::Settings {
+            lookahead_width: arg.lookahead_width,
+            lookahead_depth: arg.lookahead_depth,
+        };
+        let strategy = strategy::build_with(&arg.strategy, &settings).unwrap();
+
+        match &tree {
+            Some(tree) => {
+                Box::new(strategy::Lookup::new(tree.clone(), strategy))
             }
+            None => strategy,
         }
+    };
+
+    match &arg.command {
+        Some(Command::Bench(opts)) => {
+            return bench::run(
+                opts,
+                &lists,
+                build(),
+                arg.seed,
+                arg.hard,
+                matrix.as_deref(),
+            )
+        }
+        Some(Command::Analyze(opts)) => {
+            return analysis::run(
+                opts,
+                &lists,
+                build(),
+                &mut StdRng::seed_from_u64(seed),
+                arg.hard,
+                matrix.as_deref(),
+            )
+        }
+        Some(Command::Share(opts)) => return share::run(opts, &lists),
+        Some(Command::Tree(opts)) => {
+            return tree::run(
+                opts,
+                &lists,
+                build(),
+                seed,
+                arg.hard,
+                matrix.as_deref(),
+            )
+        }
+        None => (),
     }
 
-    if keep_words.total() > 0 {
-        vocab.preserve(&keep_words);
-    }
-
-    vocab
-}
+    let mut game = Game::new(&lists, build(), seed);
 
-fn to_lossy_string(guess: &[Hint], theme: &Theme) -> String {
-    guess.iter().map(|e| e.to_char(theme)).collect::<String>()
-}
+    if arg.hard {
+        game = game.hard(arg.verbose)
+    }
+    if let Some(matrix) = matrix {
+        game = game.with_matrix(matrix)
+    }
 
-// Preps the hint tables and the initial vocabulary. Then it enters
-// the main loop of the program where it picks a random word from its
-// vocabulary, waits for clues, then applies them to its vocabulary.
+    // When the user goes back to an earlier turn, or says they played
+    // a different word, that word has already been played so it's
+    // asked about instead of picking a new one.
+
+    let mut replay: Option<String> = None;
+    let answer = get_answer(arg, game.candidates())?;
+    let mut ui: Box<dyn protocol::Ui> = match arg.protocol {
+        protocol::Protocol::Text => Box::new(protocol::Text {
+            theme: arg.theme,
+            verbose: arg.verbose,
+            assist: arg.assist,
+            limit: arg.limit,
+        }),
+        protocol::Protocol::Json => Box::new(protocol::Json {
+            theme: arg.theme,
+            verbose: arg.verbose,
+            limit: arg.limit,
+            guesses: lists.guesses(),
+        }),
+    };
+
+    ui.start(seed)?;
+
+    'game: loop {
+        let picked = replay.is_none();
+        let mut guess = if let Some(guess) = replay.take() {
+            guess
+        } else {
+            match game.next_guess() {
+                Ok(guess) => guess.to_string(),
+                Err(Error::AllSkipped) => {
+                    ui.no_other_words()?;
+                    continue;
+                }
 
-fn main() -> io::Result<()> {
-    let arg = Args::parse();
+                // No word fits the clues. Explain which ones
+                // conflict.
+                Err(Error::NoWords) => {
+                    ui.out_of_words(&game.conflict(), game.history())?;
+                    break;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
 
-    // Prep the hint tables and start with the full vocabulary.
+        ui.guess(&protocol::Pick {
+            turn: game.turn(),
+            guess: &guess,
+            vocab: game.candidates(),
+            report: if picked { game.report() } else { None },
+            picked,
+        })?;
 
-    let mut vocab = dictionary::get_vocabulary();
-    let grn_tbl = dictionary::GreenTable::new();
-    let frq_tbl = dictionary::CharFreqTable::new();
-    let mut progress: Vec<[Hint; 5]> = Vec::with_capacity(6);
+        // If we know the secret word, generate the hints ourselves.
+        // Otherwise get them from the user. Commands that only show
+        // information ask for the hints again.
 
-    loop {
-        if vocab.total() == 0 {
-            println!("I'm out of words. Did you make a mistake with a clue?");
-            break;
-        }
+        let hints: Vec<Hint> = if let Some(answer) = &answer {
+            let hints = feedback::score(&guess, answer);
 
-        // Pick a random word from the vocabulary. This will be the
-        // guess for this iteration of the loop.
+            ui.hints(&hints)?;
+            hints
+        } else {
+            loop {
+                match ui.read(guess.len(), game.history().len())? {
+                    Input::Hints(hints) => break hints,
+
+                    // The user entered the word they played along
+                    // with its hints, so there's no need to ask.
+                    Input::Played(word, hints) => {
+                        guess = word;
+                        break hints;
+                    }
 
-        let guess = vocab.pick_word();
+                    // Rewind to the start of the earlier turn and ask
+                    // for its hints again.
+                    Input::Back(n) => {
+                        replay = Some(game.back(n)?);
+                        ui.back(game.turn())?;
+                        continue 'game;
+                    }
+                    Input::Skip => {
+                        game.skip(&guess);
+                        continue 'game;
+                    }
+                    Input::Reject => {
+                        game.reject(&guess);
 
-        if arg.verbose {
-            if vocab.total() < arg.limit {
-                println!("(vocab: {:?})", vocab);
-            } else {
-                println!("(vocabulary: {} words)", vocab.total());
+                        if let Some(path) = &arg.blacklist {
+                            dictionary::add_to_blacklist(path, &guess)?
+                        }
+                        ui.rejected(&guess)?;
+                        continue 'game;
+                    }
+                    Input::Use(word) => {
+                        replay = Some(word);
+                        continue 'game;
+                    }
+                    Input::List => ui.words(game.candidates())?,
+                    Input::Top(n) => ui.top(&game.top(n))?,
+                    Input::Stats => ui.stats(&protocol::Stats {
+                        turn: game.turn(),
+                        remaining: game.candidates().total(),
+                        bits: (game.candidates().total() as f64).log2(),
+                        strategy: &arg.strategy,
+                        hard: arg.hard,
+                        seed,
+                    })?,
+                    Input::Help => ui.help()?,
+                    Input::Quit => return Ok(()),
+                }
             }
-        }
-
-        println!("My guess: {}", guess.to_uppercase());
-
-        // Get hints from the user.
+        };
 
-        let input = get_hints()?;
+        game.apply(&guess, &hints)?;
 
-        // Convert the hint string into an array of Hint types.
+        if game.is_solved() {
+            ui.solved(game.history())?;
 
-        let hints: Vec<Hint> =
-            input.chars().map(|c| Hint::try_from(c).unwrap()).collect();
+            if arg.analyze {
+                let reports =
+                    game.analyze(build(), &mut StdRng::seed_from_u64(seed))?;
 
-        progress.push(hints.clone().try_into().unwrap());
-
-        // If every clue is green, the guess matches the secret word.
-
-        if hints.iter().all(|e| *e == Hint::Green) {
-            println!("WordleBot ??? {}/6\n", progress.len());
-            for ii in progress.iter() {
-                println!("{}", to_lossy_string(ii, &arg.theme));
+                ui.analysis(&reports)?
             }
             break;
         }
-
-        // Reduce the vocabulary by applying the hints.
-
-        vocab = process_hints(vocab, &grn_tbl, &frq_tbl, guess, &hints)
     }
     Ok(())
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    fn apply_state(hints: &Vec<Hint>) -> FreqInfo {
-        let mut info = FreqInfo::new(&hints[0]);
-
-        for hint in &hints[1..] {
-            info.update(hint)
-        }
-        info
-    }
-
-    #[test]
-    fn test_freq_info() {
-        assert_eq!(apply_state(&vec![Hint::Black]), FreqInfo(0, 0));
-        assert_eq!(apply_state(&vec![Hint::Yellow]), FreqInfo(1, 5));
-        assert_eq!(apply_state(&vec![Hint::Green]), FreqInfo(1, 5));
-
-        assert_eq!(
-            apply_state(&vec![Hint::Black, Hint::Black]),
-            FreqInfo(0, 0)
-        );
-        assert_eq!(
-            apply_state(&vec![Hint::Black, Hint::Yellow]),
-            FreqInfo(1, 1)
-        );
-        assert_eq!(
-            apply_state(&vec![Hint::Black, Hint::Green]),
-            FreqInfo(1, 1)
-        );
-
-        assert_eq!(
-            apply_state(&vec![Hint::Yellow, Hint::Black]),
-            FreqInfo(1, 1)
-        );
-        assert_eq!(
-            apply_state(&vec![Hint::Yellow, Hint::Yellow]),
-            FreqInfo(2, 5)
-        );
-        assert_eq!(
-            apply_state(&vec![Hint::Yellow, Hint::Green]),
-            FreqInfo(2, 5)
-        );
-
-        assert_eq!(
-            apply_state(&vec![Hint::Green, Hint::Black]),
-            FreqInfo(1, 1)
-        );
-        assert_eq!(
-            apply_state(&vec![Hint::Green, Hint::Yellow]),
-            FreqInfo(2, 5)
-        );
-        assert_eq!(
-            apply_state(&vec![Hint::Green, Hint::Green]),
-            FreqInfo(2, 5)
-        );
-
-        assert_eq!(
-            apply_state(&vec![Hint::Black, Hint::Black, Hint::Black]),
-            FreqInfo(0, 0)
-        );
-        assert_eq!(
-            apply_state(&vec![Hint::Black, Hint::Yellow, Hint::Black]),
-            FreqInfo(1, 1)
-        );
-        assert_eq!(
-            apply_state(&vec![Hint::Black, Hint::Green, Hint::Black]),
-            FreqInfo(1, 1)
-        );
-
-        assert_eq!(
-            apply_state(&vec![Hint::Green, Hint::Yellow, Hint::Black]),
-            FreqInfo(2, 2)
-        );
-    }
-
-    #[test]
-    fn test_freq_info_table() {
-        {
-            let tbl = bld_freq_info_table(
-                &vec![
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                ],
-                "abcde",
-            );
-            let mut expected: Map<char, FreqInfo> = Map::new();
-
-            expected.insert('a', FreqInfo(0, 0));
-            expected.insert('b', FreqInfo(0, 0));
-            expected.insert('c', FreqInfo(0, 0));
-            expected.insert('d', FreqInfo(0, 0));
-            expected.insert('e', FreqInfo(0, 0));
-
-            assert_eq!(tbl, expected);
-        }
-        {
-            let tbl = bld_freq_info_table(
-                &vec![
-                    Hint::Yellow,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                ],
-                "abcde",
-            );
-            let mut expected: Map<char, FreqInfo> = Map::new();
-
-            expected.insert('a', FreqInfo(1, 5));
-            expected.insert('b', FreqInfo(0, 0));
-            expected.insert('c', FreqInfo(0, 0));
-            expected.insert('d', FreqInfo(0, 0));
-            expected.insert('e', FreqInfo(0, 0));
-
-            assert_eq!(tbl, expected);
-        }
-        {
-            let tbl = bld_freq_info_table(
-                &vec![
-                    Hint::Yellow,
-                    Hint::Yellow,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                ],
-                "aabcd",
-            );
-            let mut expected: Map<char, FreqInfo> = Map::new();
-
-            expected.insert('a', FreqInfo(2, 5));
-            expected.insert('b', FreqInfo(0, 0));
-            expected.insert('c', FreqInfo(0, 0));
-            expected.insert('d', FreqInfo(0, 0));
-
-            assert_eq!(tbl, expected);
-        }
-        {
-            let tbl = bld_freq_info_table(
-                &vec![
-                    Hint::Yellow,
-                    Hint::Yellow,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                ],
-                "aaabc",
-            );
-            let mut expected: Map<char, FreqInfo> = Map::new();
-
-            expected.insert('a', FreqInfo(2, 2));
-            expected.insert('b', FreqInfo(0, 0));
-            expected.insert('c', FreqInfo(0, 0));
-
-            assert_eq!(tbl, expected);
-        }
-        {
-            let tbl = bld_freq_info_table(
-                &vec![
-                    Hint::Yellow,
-                    Hint::Yellow,
-                    Hint::Black,
-                    Hint::Yellow,
-                    Hint::Black,
-                ],
-                "aaabc",
-            );
-            let mut expected: Map<char, FreqInfo> = Map::new();
-
-            expected.insert('a', FreqInfo(2, 2));
-            expected.insert('b', FreqInfo(1, 3));
-            expected.insert('c', FreqInfo(0, 0));
-
-            assert_eq!(tbl, expected);
-        }
-        {
-            let tbl = bld_freq_info_table(
-                &vec![
-                    Hint::Yellow,
-                    Hint::Yellow,
-                    Hint::Black,
-                    Hint::Yellow,
-                    Hint::Black,
-                ],
-                "aacbd",
-            );
-            let mut expected: Map<char, FreqInfo> = Map::new();
-
-            expected.insert('a', FreqInfo(2, 4));
-            expected.insert('b', FreqInfo(1, 3));
-            expected.insert('c', FreqInfo(0, 0));
-            expected.insert('d', FreqInfo(0, 0));
-
-            assert_eq!(tbl, expected);
-        }
-    }
-
-    #[test]
-    fn test_position_hints() {
-        {
-            let mut vocab =
-                dictionary::Words::new(&["aaaaa", "bbbba", "cccac"]);
-            let gt = dictionary::mk_green_tbl(&["aaaaa", "bbbba", "cccac"]);
-            let expected = dictionary::Words::new(&["aaaaa", "bbbba"]);
-
-            process_position_hints(
-                &mut vocab,
-                &gt,
-                "aaaaa",
-                &vec![
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Green,
-                ],
-            );
-            assert_eq!(vocab, expected);
-        }
-
-        {
-            let mut vocab =
-                dictionary::Words::new(&["aaaaa", "bbbba", "cccac"]);
-            let gt = dictionary::mk_green_tbl(&["aaaaa", "bbbba", "cccac"]);
-            let expected = dictionary::Words::new(&["cccac"]);
-
-            process_position_hints(
-                &mut vocab,
-                &gt,
-                "aaaaa",
-                &vec![
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Yellow,
-                ],
-            );
-            assert_eq!(vocab, expected);
-        }
-
-        {
-            let mut vocab =
-                dictionary::Words::new(&["aaaaa", "aacab", "bbbba", "cccac"]);
-            let gt =
-                dictionary::mk_green_tbl(&["aaaaa", "aacab", "bbbba", "cccac"]);
-            let expected = dictionary::Words::new(&["aacab"]);
-
-            process_position_hints(
-                &mut vocab,
-                &gt,
-                "aaaac",
-                &vec![
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Black,
-                    Hint::Green,
-                    Hint::Yellow,
-                ],
-            );
-            assert_eq!(vocab, expected);
-        }
+fn main() {
+    if let Err(e) = run(&Args::parse()) {
+        eprintln!("ERROR: {}", e);
+        std::process::exit(1)
     }
 }
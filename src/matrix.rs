@@ -0,0 +1,432 @@
+use crate::dictionary::{CharFreqTable, GreenTable, WordLists, Words};
+use crate::{feedback, Hint};
+use rayon::prelude::*;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// The pattern matrix holds the hints every guess gets against every
+// answer, encoded by `feedback::pattern()`. The strategies and the
+// hint processing need these over and over, so they're computed once
+// for the word lists and saved to a cache file.
+//
+// The cache file starts with a header: the magic bytes, the version,
+// the key of the word lists, the number of rows and columns and the
+// size of each cell. The cells follow, row by row, in little-endian
+// order. A file with a different version or key is recomputed.
+
+const MAGIC: &[u8; 8] = b"WBOTPATS";
+
+// Bump this whenever the file layout, or the way patterns are
+// computed, changes.
+
+const VERSION: u32 = 1;
+
+const HEADER: usize = 8 + 4 + 8 + 4 + 4 + 1;
+
+// The column of words that aren't answers.
+
+const NONE: u32 = u32::MAX;
+
+// Sets smaller than this are quicker to score directly than to look
+// up, since finding the row and checking the columns costs more than
+// a few patterns.
+
+const SMALL: usize = 32;
+
+// Words of up to five letters have at most 243 patterns, which fit in
+// a byte. Longer words need two.
+
+#[derive(PartialEq, Debug)]
+enum Cells {
+    Narrow(Vec<u8>),
+    Wide(Vec<u16>),
+}
+
+pub struct Matrix {
+    key: u64,
+
+    // The rows are every word in the lists and the columns are the
+    // answers, both in alphabetical order.
+    guesses: Words,
+    answers: Words,
+
+    // The column of each word in the lists' index, and the number of
+    // columns.
+    columns: Vec<u32>,
+    width: usize,
+    cells: Cells,
+}
+
+// Returns a hash of the word lists, used to name the cache file and
+// to make sure it matches the lists. It's FNV-1a, which is stable
+// from one build to the next.
+
+fn key(answers: &Words, guesses: &Words) -> u64 {
+    answers
+        .iter()
+        .chain([""])
+        .chain(guesses.iter())
+        .flat_map(|e| e.bytes().chain([b'\n']))
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+impl Matrix {
+    // Computes the matrix for the word lists. The rows are computed
+    // in parallel.
+
+    pub fn new(lists: &WordLists) -> Matrix {
+        Matrix::with_cells(lists, |guesses, answers| {
+            let answers: Vec<&str> = answers.iter().collect();
+            let row = |guess: &str| {
+                answers
+                    .iter()
+                    .map(|answer| feedback::pattern(guess, answer))
+                    .collect::<Vec<u16>>()
+            };
+            let rows = guesses.iter().collect::<Vec<_>>();
+
+            if feedback::patterns(lists.word_len()) <= 256 {
+                Cells::Narrow(
+                    rows.par_iter()
+                        .flat_map_iter(|e| row(e).into_iter().map(|p| p as u8))
+                        .collect(),
+                )
+            } else {
+                Cells::Wide(rows.par_iter().flat_map_iter(|e| row(e)).collect())
+            }
+        })
+    }
+
+    fn with_cells(
+        lists: &WordLists, cells: impl FnOnce(&Words, &Words) -> Cells,
+    ) -> Matrix {
+        let guesses = lists.guesses();
+        let answers = lists.vocabulary();
+        let mut columns = vec![NONE; guesses.total()];
+
+        for (col, pos) in answers.positions().enumerate() {
+            columns[pos] = col as u32
+        }
+
+        Matrix {
+            key: key(&answers, &guesses),
+            cells: cells(&guesses, &answers),
+            width: answers.total(),
+            guesses,
+            answers,
+            columns,
+        }
+    }
+
+    // Returns the matrix for the word lists, reading it from the
+    // cache in `dir` if it's there. Otherwise it's computed and saved
+    // in `dir`, which is created if needed.
+
+    pub fn load(lists: &WordLists, dir: &Path) -> io::Result<Matrix> {
+        let key = key(&lists.vocabulary(), &lists.guesses());
+        let path = dir.join(format!("patterns-{:016x}.bin", key));
+        let error = |e: io::Error| {
+            io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+        };
+
+        match fs::read(&path) {
+            Ok(data) => {
+                let rows = lists.guesses().total();
+                let cols = lists.vocabulary().total();
+
+                if let Some(cells) = decode(&data, key, rows, cols) {
+                    return Ok(Matrix::with_cells(lists, |_, _| cells));
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(error(e)),
+        }
+
+        let matrix = Matrix::new(lists);
+
+        // Write to a temporary file first so another session never
+        // reads a partial matrix.
+
+        let tmp = path.with_extension("tmp");
+
+        fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&tmp, matrix.encode()))
+            .and_then(|_| fs::rename(&tmp, &path))
+            .map_err(error)?;
+        Ok(matrix)
+    }
+
+    fn cell(&self, row: usize, col: u32) -> u16 {
+        let idx = row * self.width + col as usize;
+
+        match &self.cells {
+            Cells::Narrow(cells) => cells[idx] as u16,
+            Cells::Wide(cells) => cells[idx],
+        }
+    }
+
+    // Returns the pattern `guess` gets if `answer` is the answer, or
+    // `None` if either word isn't in the matrix.
+
+    pub fn pattern(&self, guess: &str, answer: &str) -> Option<u16> {
+        let row = self.guesses.position(guess)?;
+        let col = self.columns[self.answers.position(answer)?];
+
+        (col != NONE).then(|| self.cell(row, col))
+    }
+
+    // Returns `true` if the matrix has a column for every word in
+    // `words`: they were made from the same lists and can all be the
+    // answer.
+
+    fn covers(&self, words: &Words) -> bool {
+        words.shares_index(&self.guesses)
+            && words.positions().all(|pos| self.columns[pos] != NONE)
+    }
+
+    // Returns the pattern `guess` gets against each word in `words`,
+    // in the order `Words::iter()` returns them. The strategies score
+    // guesses with these. `None` is returned if the matrix can't be
+    // used, like `filter()`, or if `words` is too small to gain from
+    // it.
+
+    pub fn row<'a>(
+        &'a self, guess: &str, words: &'a Words,
+    ) -> Option<impl Iterator<Item = u16> + 'a> {
+        if words.total() < SMALL {
+            return None;
+        }
+        let row = self.guesses.position(guess)?;
+
+        self.covers(words).then(|| {
+            words
+                .positions()
+                .map(move |pos| self.cell(row, self.columns[pos]))
+        })
+    }
+
+    // Returns the words in `vocab` that would give `hints` for
+    // `guess`. It does the job of `process_hints()` with a single
+    // look-up per word and is exact: `process_hints()` can leave a
+    // few words that don't fit when the guess repeats a letter.
+    // `None` is returned if the matrix can't be used: `guess` isn't
+    // in the lists, or `vocab` wasn't made from the same lists or
+    // holds words that can't be the answer.
+
+    pub fn filter(
+        &self, vocab: &Words, guess: &str, hints: &[Hint],
+    ) -> Option<Words> {
+        let row = self.guesses.position(guess)?;
+        let pattern = feedback::encode(hints);
+
+        if !self.covers(vocab) {
+            return None;
+        }
+
+        let mut vocab = vocab.clone();
+
+        vocab.retain(|pos| self.cell(row, self.columns[pos]) == pattern);
+        Some(vocab)
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(HEADER);
+        let (width, cells) = match &self.cells {
+            Cells::Narrow(cells) => (1u8, cells.clone()),
+            Cells::Wide(cells) => {
+                (2u8, cells.iter().flat_map(|e| e.to_le_bytes()).collect())
+            }
+        };
+
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&VERSION.to_le_bytes());
+        data.extend_from_slice(&self.key.to_le_bytes());
+        data.extend_from_slice(&(self.guesses.total() as u32).to_le_bytes());
+        data.extend_from_slice(&(self.answers.total() as u32).to_le_bytes());
+        data.push(width);
+        data.extend(cells);
+        data
+    }
+}
+
+// Uses the clues to reduce the vocabulary. The matrix is used if
+// there is one and it can be; otherwise it's up to
+// `crate::process_hints()`.
+
+pub fn process_hints(
+    matrix: Option<&Matrix>, vocab: Words, gt: &GreenTable, ft: &CharFreqTable,
+    guess: &str, hints: &[Hint],
+) -> Words {
+    match matrix.and_then(|e| e.filter(&vocab, guess, hints)) {
+        Some(words) => words,
+        None => crate::process_hints(vocab, gt, ft, guess, hints),
+    }
+}
+
+// Returns the cells saved in a cache file, or `None` if the file
+// doesn't hold the expected matrix.
+
+fn decode(data: &[u8], key: u64, rows: usize, cols: usize) -> Option<Cells> {
+    if data.len() < HEADER || &data[..8] != MAGIC {
+        return None;
+    }
+
+    let u32_at =
+        |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap());
+    let cells = &data[HEADER..];
+
+    if u32_at(8) != VERSION
+        || u64::from_le_bytes(data[12..20].try_into().unwrap()) != key
+        || u32_at(20) as usize != rows
+        || u32_at(24) as usize != cols
+    {
+        return None;
+    }
+
+    match data[28] {
+        1 if cells.len() == rows * cols => Some(Cells::Narrow(cells.to_vec())),
+        2 if cells.len() == 2 * rows * cols => Some(Cells::Wide(
+            cells
+                .chunks_exact(2)
+                .map(|e| u16::from_le_bytes([e[0], e[1]]))
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_hints;
+    use std::path::PathBuf;
+
+    fn lists() -> WordLists {
+        WordLists::with_lists(
+            vec!["camel", "hazel", "otter", "tatty", "geese", "eerie"],
+            vec!["aahed", "teeth", "zymic"],
+        )
+    }
+
+    #[test]
+    fn test_pattern() {
+        let matrix = Matrix::new(&lists());
+
+        for guess in ["aahed", "camel", "teeth", "zymic"] {
+            for answer in ["eerie", "otter", "tatty"] {
+                assert_eq!(
+                    matrix.pattern(guess, answer),
+                    Some(feedback::pattern(guess, answer))
+                )
+            }
+        }
+        assert_eq!(matrix.pattern("teeth", "zymic"), None);
+        assert_eq!(matrix.pattern("abcde", "otter"), None);
+
+        let matrix = Matrix::new(&WordLists::with_lists(
+            vec!["camels", "otters"],
+            vec![],
+        ));
+
+        assert!(matches!(matrix.cells, Cells::Wide(_)));
+        assert_eq!(
+            matrix.pattern("camels", "otters"),
+            Some(feedback::pattern("camels", "otters"))
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let lists = lists();
+        let matrix = Matrix::new(&lists);
+        let vocab = lists.vocabulary();
+        let gt = lists.green_table();
+        let ft = lists.freq_table();
+
+        // The matrix keeps exactly the words that fit, which
+        // `process_hints()` keeps too.
+
+        for guess in lists.guesses().iter() {
+            for answer in vocab.iter() {
+                let hints = feedback::score(guess, answer);
+                let words = matrix.filter(&vocab, guess, &hints).unwrap();
+                let fits: Vec<&str> = vocab
+                    .iter()
+                    .filter(|e| feedback::score(guess, e) == hints)
+                    .collect();
+                let mut all =
+                    process_hints(vocab.clone(), &gt, &ft, guess, &hints);
+
+                assert_eq!(words, Words::new(&fits));
+                all.preserve(&words);
+                assert_eq!(all, words);
+            }
+        }
+
+        // Words the matrix doesn't know about can't be filtered.
+
+        let hints = feedback::score("otter", "camel");
+
+        assert_eq!(matrix.filter(&vocab, "abcde", &hints), None);
+        assert_eq!(matrix.filter(&lists.guesses(), "otter", &hints), None);
+        assert_eq!(
+            matrix.filter(&Words::new(&["camel"]), "otter", &hints),
+            None
+        );
+    }
+
+    #[test]
+    fn test_row() {
+        let builtin = WordLists::new();
+        let lists = WordLists::with_lists(
+            builtin.vocabulary().iter().take(SMALL).collect(),
+            builtin.guesses().iter().take(SMALL * 2).collect(),
+        );
+        let matrix = Matrix::new(&lists);
+        let vocab = lists.vocabulary();
+
+        for guess in lists.guesses().iter() {
+            assert!(matrix
+                .row(guess, &vocab)
+                .unwrap()
+                .eq(vocab.iter().map(|e| feedback::pattern(guess, e))));
+        }
+
+        let first = vocab.iter().next().unwrap();
+        let mut small = vocab.clone();
+
+        small.retain_words(|e| e != first);
+        assert!(matrix.row("abcde", &vocab).is_none());
+        assert!(matrix.row(first, &lists.guesses()).is_none());
+        assert!(matrix.row(first, &small).is_none());
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir: PathBuf = std::env::temp_dir()
+            .join(format!("wordlebot-test-{}", std::process::id()));
+        let lists = lists();
+        let matrix = Matrix::load(&lists, &dir).unwrap();
+        let path = dir.join(format!("patterns-{:016x}.bin", matrix.key));
+
+        assert_eq!(Matrix::load(&lists, &dir).unwrap().cells, matrix.cells);
+
+        // A damaged file gets recomputed.
+
+        fs::write(&path, b"WBOTPATS").unwrap();
+        assert_eq!(Matrix::load(&lists, &dir).unwrap().cells, matrix.cells);
+        assert_eq!(fs::read(&path).unwrap(), matrix.encode());
+
+        // Different lists use a different file.
+
+        let other = WordLists::with_lists(vec!["camel"], vec![]);
+
+        assert_ne!(Matrix::load(&other, &dir).unwrap().key, matrix.key);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,650 @@
+use crate::cli::analysis;
+use crate::cli::input::{self, Input};
+use clap::ArgEnum;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+use wordlebot::analysis::TurnReport;
+use wordlebot::conflict::Conflict;
+use wordlebot::{
+    dictionary::Words, to_hint_string, to_lossy_string, Hint, Theme,
+};
+
+// The ways wordlebot can talk to whoever is giving it hints.
+
+#[derive(Copy, Clone, PartialEq, Eq, ArgEnum, Debug)]
+pub enum Protocol {
+    Text,
+    Json,
+}
+
+// Describes the guess made at the start of a turn.
+
+pub struct Pick<'a> {
+    // The turn number, starting at 1.
+    pub turn: usize,
+    pub guess: &'a str,
+
+    // The words that could be the answer.
+    pub vocab: &'a Words,
+
+    // The strategy's description of how it chose the guess. It's
+    // `None` if the guess wasn't picked by the strategy (e.g. the
+    // user went back to an earlier turn.)
+    pub report: Option<String>,
+
+    // Set if the guess was picked by the strategy.
+    pub picked: bool,
+}
+
+// A summary of the game so far.
+
+#[derive(Serialize, Debug)]
+pub struct Stats<'a> {
+    pub turn: usize,
+    pub remaining: usize,
+    pub bits: f64,
+    pub strategy: &'a str,
+    pub hard: bool,
+    pub seed: u64,
+}
+
+// Everything the game loop says to, or asks of, the user. The loop
+// only deals with game state; how it's shown, and how input is read,
+// is up to the protocol.
+
+pub trait Ui {
+    fn start(&mut self, seed: u64) -> io::Result<()>;
+    fn guess(&mut self, pick: &Pick) -> io::Result<()>;
+
+    // Shows the hints wordlebot generated when it knows the answer.
+    fn hints(&mut self, hints: &[Hint]) -> io::Result<()>;
+
+    // Returns the hints, or a command, for a guess of `len` letters
+    // after `turns` turns.
+    fn read(&mut self, len: usize, turns: usize) -> io::Result<Input>;
+
+    fn solved(&mut self, history: &[(String, Vec<Hint>)]) -> io::Result<()>;
+    fn analysis(&mut self, reports: &[TurnReport]) -> io::Result<()>;
+    fn out_of_words(
+        &mut self, conflict: &Conflict, history: &[(String, Vec<Hint>)],
+    ) -> io::Result<()>;
+
+    // Every remaining word was skipped.
+    fn no_other_words(&mut self) -> io::Result<()>;
+    fn back(&mut self, turn: usize) -> io::Result<()>;
+    fn rejected(&mut self, word: &str) -> io::Result<()>;
+    fn words(&mut self, vocab: &Words) -> io::Result<()>;
+    fn top(&mut self, ranked: &[(&'static str, f64, bool)]) -> io::Result<()>;
+    fn stats(&mut self, stats: &Stats) -> io::Result<()>;
+    fn help(&mut self) -> io::Result<()>;
+}
+
+// The protocol meant for people.
+
+pub struct Text {
+    pub theme: Theme,
+    pub verbose: bool,
+    pub assist: bool,
+    pub limit: usize,
+}
+
+impl Text {
+    fn print_words(vocab: &Words) {
+        let words: Vec<String> =
+            vocab.iter().map(|e| e.to_uppercase()).collect();
+
+        for line in words.chunks(10) {
+            println!("  {}", line.join(" "))
+        }
+        println!("({} words)", words.len());
+    }
+}
+
+impl Ui for Text {
+    fn start(&mut self, seed: u64) -> io::Result<()> {
+        if self.verbose {
+            println!("(seed: {})", seed);
+        }
+        Ok(())
+    }
+
+    fn guess(&mut self, pick: &Pick) -> io::Result<()> {
+        if pick.picked {
+            // In assist mode, the user is going to pick the next
+            // word so the remaining words are always shown.
+
+            if self.assist {
+                if pick.vocab.total() < self.limit {
+                    Text::print_words(pick.vocab)
+                } else {
+                    println!("({} words left)", pick.vocab.total());
+                }
+            } else if self.verbose {
+                if pick.vocab.total() < self.limit {
+                    println!("(vocab: {:?})", pick.vocab);
+                } else {
+                    println!("(vocabulary: {} words)", pick.vocab.total());
+                }
+            }
+
+            if self.verbose {
+                if let Some(report) = &pick.report {
+                    println!("({})", report);
+                }
+            }
+        }
+
+        if self.assist {
+            println!("I suggest: {}", pick.guess.to_uppercase());
+        } else {
+            println!("My guess: {}", pick.guess.to_uppercase());
+        }
+        Ok(())
+    }
+
+    fn hints(&mut self, hints: &[Hint]) -> io::Result<()> {
+        println!("   Hints> {}", to_hint_string(hints));
+        Ok(())
+    }
+
+    // The loop is so the input can be re-entered if the user entered
+    // something invalid. Reaching the end of the input is treated as
+    // `quit`.
+
+    fn read(&mut self, len: usize, turns: usize) -> io::Result<Input> {
+        let prompt = if self.assist { "Guess" } else { "Hints" };
+
+        loop {
+            let mut input = String::new();
+
+            // Prompt the user and get the hints.
+
+            print!("   {}> ", prompt);
+            io::stdout().flush()?;
+
+            if io::stdin().read_line(&mut input)? == 0 {
+                println!();
+                return Ok(Input::Quit);
+            }
+
+            match input::parse(&input, len, turns) {
+                Ok(input) => return Ok(input),
+                Err(e) => println!("ERROR: {}", e),
+            }
+        }
+    }
+
+    fn solved(&mut self, history: &[(String, Vec<Hint>)]) -> io::Result<()> {
+        println!("WordleBot ??? {}/6\n", history.len());
+        for (_, ii) in history.iter() {
+            println!("{}", to_lossy_string(ii, &self.theme));
+        }
+        Ok(())
+    }
+
+    fn analysis(&mut self, reports: &[TurnReport]) -> io::Result<()> {
+        println!();
+        analysis::print(reports, &mut io::stdout().lock())
+    }
+
+    fn out_of_words(
+        &mut self, conflict: &Conflict, history: &[(String, Vec<Hint>)],
+    ) -> io::Result<()> {
+        println!("I'm out of words. Did you make a mistake with a clue?");
+        conflict.print(history);
+        Ok(())
+    }
+
+    fn no_other_words(&mut self) -> io::Result<()> {
+        println!("I don't have any other words to try.");
+        Ok(())
+    }
+
+    fn back(&mut self, turn: usize) -> io::Result<()> {
+        println!("Going back to guess {}.", turn);
+        Ok(())
+    }
+
+    fn rejected(&mut self, word: &str) -> io::Result<()> {
+        println!("I won't guess {} again.", word.to_uppercase());
+        Ok(())
+    }
+
+    fn words(&mut self, vocab: &Words) -> io::Result<()> {
+        Text::print_words(vocab);
+        Ok(())
+    }
+
+    // Words that could be the answer are marked with an asterisk.
+
+    fn top(&mut self, ranked: &[(&'static str, f64, bool)]) -> io::Result<()> {
+        for (idx, (word, bits, candidate)) in ranked.iter().enumerate() {
+            println!(
+                "{:>4}. {} {:.2} bits{}",
+                idx + 1,
+                word.to_uppercase(),
+                bits,
+                if *candidate { " *" } else { "" }
+            )
+        }
+        Ok(())
+    }
+
+    fn stats(&mut self, stats: &Stats) -> io::Result<()> {
+        println!("Guesses so far: {}", stats.turn - 1);
+        println!("Words left: {} ({:.2} bits)", stats.remaining, stats.bits);
+        println!(
+            "Strategy: {}{}",
+            stats.strategy,
+            if stats.hard { " (hard mode)" } else { "" }
+        );
+        println!("Seed: {}", stats.seed);
+        Ok(())
+    }
+
+    fn help(&mut self) -> io::Result<()> {
+        println!("{}", input::HELP);
+        Ok(())
+    }
+}
+
+// A request read by the JSON protocol. Exactly one of `hints` and
+// `command` must be given. `guess` may accompany `hints` when the
+// word played wasn't wordlebot's guess. Commands are the ones
+// accepted at the text protocol's prompt.
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct Request {
+    guess: Option<String>,
+    hints: Option<String>,
+    command: Option<String>,
+}
+
+// The objects the JSON protocol writes. Each is written on its own
+// line and has a "type" field naming the variant.
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event<'a> {
+    Start {
+        seed: u64,
+    },
+    Guess {
+        turn: usize,
+        guess: &'a str,
+        remaining: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        candidates: Option<Vec<&'static str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        report: Option<&'a str>,
+    },
+    Hints {
+        hints: String,
+    },
+    Solved {
+        turns: usize,
+        grid: Vec<String>,
+    },
+    Analysis {
+        turns: Vec<TurnAnalysis>,
+    },
+    OutOfWords {
+        turns: Vec<usize>,
+        reasons: &'a [String],
+        fixes: Vec<FixDescription>,
+    },
+    NoOtherWords,
+    Back {
+        turn: usize,
+    },
+    Rejected {
+        word: &'a str,
+    },
+    Candidates {
+        words: Vec<&'static str>,
+    },
+    Top {
+        guesses: Vec<Suggestion>,
+    },
+    Stats(&'a Stats<'a>),
+    Help {
+        text: &'static str,
+    },
+    Error {
+        code: &'static str,
+        message: String,
+    },
+}
+
+#[derive(Serialize, Debug)]
+struct TurnAnalysis {
+    guess: String,
+    before: usize,
+    after: usize,
+    gained: f64,
+    expected: f64,
+    best: &'static str,
+    best_expected: f64,
+    skill: f64,
+    luck: f64,
+    bot: &'static str,
+}
+
+#[derive(Serialize, Debug)]
+struct FixDescription {
+    turn: usize,
+    hints: String,
+    words: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct Suggestion {
+    word: &'static str,
+    bits: f64,
+    candidate: bool,
+}
+
+// The protocol meant for programs. Turns are numbered from 1 and
+// hints use the B/Y/G letters of the text protocol.
+
+pub struct Json {
+    pub theme: Theme,
+    pub verbose: bool,
+    pub limit: usize,
+
+    // The words a request may say were played.
+    pub guesses: Words,
+}
+
+impl Json {
+    fn send(event: &Event) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+
+        serde_json::to_writer(&mut out, event)?;
+        writeln!(out)?;
+        out.flush()
+    }
+
+    // Returns the remaining words, if there are few enough to list.
+
+    fn candidates(&self, vocab: &Words) -> Option<Vec<&'static str>> {
+        if vocab.total() < self.limit {
+            Some(vocab.iter().collect())
+        } else {
+            None
+        }
+    }
+}
+
+// Turns a line of input into the same `Input` the text protocol
+// produces. Errors are returned as the code and message of the error
+// object to send back. Only `command` is parsed as a command; `hints`
+// has to be hints and `guess` one of `guesses`.
+
+fn parse_request(
+    line: &str, len: usize, turns: usize, guesses: &Words,
+) -> Result<Input, (&'static str, String)> {
+    let request: Request = serde_json::from_str(line)
+        .map_err(|e| ("invalid_json", e.to_string()))?;
+    let invalid = |e: String| ("invalid_input", e);
+
+    match request {
+        Request {
+            guess: None,
+            hints: Some(hints),
+            command: None,
+        } => input::hints(hints.trim(), len)
+            .map(Input::Hints)
+            .map_err(invalid),
+        Request {
+            guess: Some(guess),
+            hints: Some(hints),
+            command: None,
+        } => {
+            let guess = guess.trim().to_lowercase();
+
+            if !guesses.contains(&guess) {
+                return Err(invalid(format!(
+                    "\"{}\" isn't an allowed guess",
+                    guess.to_uppercase()
+                )));
+            }
+            input::hints(hints.trim(), len)
+                .map(|hints| Input::Played(guess, hints))
+                .map_err(invalid)
+        }
+        Request {
+            guess: None,
+            hints: None,
+            command: Some(command),
+        } => input::parse(&command, len, turns).map_err(invalid),
+        _ => Err((
+            "invalid_request",
+            "a request needs either \"hints\" (and optionally \
+             \"guess\") or \"command\""
+                .to_string(),
+        )),
+    }
+}
+
+impl Ui for Json {
+    fn start(&mut self, seed: u64) -> io::Result<()> {
+        Json::send(&Event::Start { seed })
+    }
+
+    fn guess(&mut self, pick: &Pick) -> io::Result<()> {
+        Json::send(&Event::Guess {
+            turn: pick.turn,
+            guess: pick.guess,
+            remaining: pick.vocab.total(),
+            candidates: self.candidates(pick.vocab),
+            report: pick.report.as_deref().filter(|_| self.verbose),
+        })
+    }
+
+    fn hints(&mut self, hints: &[Hint]) -> io::Result<()> {
+        Json::send(&Event::Hints {
+            hints: to_hint_string(hints),
+        })
+    }
+
+    // Invalid requests are answered with an error object and another
+    // request is read. Reaching the end of the input is treated as
+    // `quit`.
+
+    fn read(&mut self, len: usize, turns: usize) -> io::Result<Input> {
+        let stdin = io::stdin();
+
+        loop {
+            let mut line = String::new();
+
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Ok(Input::Quit);
+            }
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match parse_request(&line, len, turns, &self.guesses) {
+                Ok(input) => return Ok(input),
+                Err((code, message)) => {
+                    Json::send(&Event::Error { code, message })?
+                }
+            }
+        }
+    }
+
+    fn solved(&mut self, history: &[(String, Vec<Hint>)]) -> io::Result<()> {
+        Json::send(&Event::Solved {
+            turns: history.len(),
+            grid: history
+                .iter()
+                .map(|(_, hints)| to_lossy_string(hints, &self.theme))
+                .collect(),
+        })
+    }
+
+    fn analysis(&mut self, reports: &[TurnReport]) -> io::Result<()> {
+        Json::send(&Event::Analysis {
+            turns: reports
+                .iter()
+                .map(|e| TurnAnalysis {
+                    guess: e.guess.clone(),
+                    before: e.before,
+                    after: e.after,
+                    gained: e.gained,
+                    expected: e.expected,
+                    best: e.best.0,
+                    best_expected: e.best.1,
+                    skill: e.skill(),
+                    luck: e.luck(),
+                    bot: e.bot,
+                })
+                .collect(),
+        })
+    }
+
+    fn out_of_words(
+        &mut self, conflict: &Conflict, history: &[(String, Vec<Hint>)],
+    ) -> io::Result<()> {
+        Json::send(&Event::OutOfWords {
+            turns: conflict.turns.iter().map(|e| e + 1).collect(),
+            reasons: &conflict.reasons,
+            fixes: conflict
+                .fixes
+                .iter()
+                .map(|fix| {
+                    let mut hints = history[fix.turn].1.clone();
+
+                    hints[fix.position] = fix.hint;
+                    FixDescription {
+                        turn: fix.turn + 1,
+                        hints: to_hint_string(&hints),
+                        words: fix.words,
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    fn no_other_words(&mut self) -> io::Result<()> {
+        Json::send(&Event::NoOtherWords)
+    }
+
+    fn back(&mut self, turn: usize) -> io::Result<()> {
+        Json::send(&Event::Back { turn })
+    }
+
+    fn rejected(&mut self, word: &str) -> io::Result<()> {
+        Json::send(&Event::Rejected { word })
+    }
+
+    fn words(&mut self, vocab: &Words) -> io::Result<()> {
+        Json::send(&Event::Candidates {
+            words: vocab.iter().collect(),
+        })
+    }
+
+    fn top(&mut self, ranked: &[(&'static str, f64, bool)]) -> io::Result<()> {
+        Json::send(&Event::Top {
+            guesses: ranked
+                .iter()
+                .map(|(word, bits, candidate)| Suggestion {
+                    word,
+                    bits: *bits,
+                    candidate: *candidate,
+                })
+                .collect(),
+        })
+    }
+
+    fn stats(&mut self, stats: &Stats) -> io::Result<()> {
+        Json::send(&Event::Stats(stats))
+    }
+
+    fn help(&mut self) -> io::Result<()> {
+        Json::send(&Event::Help { text: input::HELP })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Hint::{Black as B, Green as G, Yellow as Y};
+
+    #[test]
+    fn test_parse_request() {
+        let guesses = Words::new(&["crane", "otter"]);
+        let parse = |line, turns| parse_request(line, 5, turns, &guesses);
+        let code = |line| parse(line, 1).unwrap_err().0;
+
+        assert_eq!(
+            parse(r#"{"hints": "bygbb"}"#, 0),
+            Ok(Input::Hints(vec![B, Y, G, B, B]))
+        );
+        assert_eq!(
+            parse(r#"{"guess": "CRANE", "hints": "GGGGG"}"#, 0),
+            Ok(Input::Played("crane".into(), vec![G; 5]))
+        );
+        assert_eq!(parse(r#"{"command": "top 3"}"#, 0), Ok(Input::Top(3)));
+        assert_eq!(parse(r#"{"command": "undo"}"#, 1), Ok(Input::Back(1)));
+
+        assert_eq!(code("BYGBB"), "invalid_json");
+        assert_eq!(code(r#"{"hint": "BYGBB"}"#), "invalid_json");
+        assert_eq!(code(r#"{}"#), "invalid_request");
+        assert_eq!(code(r#"{"guess": "crane"}"#), "invalid_request");
+        assert_eq!(
+            code(r#"{"hints": "BYGBB", "command": "undo"}"#),
+            "invalid_request"
+        );
+        assert_eq!(code(r#"{"hints": "BYGB"}"#), "invalid_input");
+        assert_eq!(code(r#"{"command": ":back 2"}"#), "invalid_input");
+        assert_eq!(
+            code(r#"{"guess": "zzzzz", "hints": "GGGGG"}"#),
+            "invalid_input"
+        );
+
+        // Commands are only taken from "command".
+
+        for line in [
+            r#"{"hints": "list"}"#,
+            r#"{"hints": "quit"}"#,
+            r#"{"hints": "undo"}"#,
+            r#"{"guess": "top", "hints": "3"}"#,
+            r#"{"guess": "use", "hints": "crane"}"#,
+            r#"{"guess": "crane", "hints": "skip"}"#,
+        ] {
+            assert_eq!(code(line), "invalid_input", "{}", line)
+        }
+    }
+
+    #[test]
+    fn test_events() {
+        let json = |event| serde_json::to_string(&event).unwrap();
+
+        assert_eq!(
+            json(Event::Back { turn: 2 }),
+            r#"{"type":"back","turn":2}"#
+        );
+        assert_eq!(json(Event::NoOtherWords), r#"{"type":"no_other_words"}"#);
+        assert_eq!(
+            json(Event::Guess {
+                turn: 1,
+                guess: "crane",
+                remaining: 2,
+                candidates: Some(vec!["camel", "hazel"]),
+                report: None,
+            }),
+            r#"{"type":"guess","turn":1,"guess":"crane","remaining":2,"candidates":["camel","hazel"]}"#
+        );
+        assert_eq!(
+            json(Event::Error {
+                code: "invalid_input",
+                message: "oops".to_string(),
+            }),
+            r#"{"type":"error","code":"invalid_input","message":"oops"}"#
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::heuristic::Heuristic;
+use super::{Strategy, Turn};
+use rand::RngCore;
+
+// Returns a bit mask of the letters in `word`.
+
+fn letters(word: &str) -> u32 {
+    word.bytes().fold(0, |acc, ch| acc | 1 << (ch - b'a'))
+}
+
+// Plays "burner" guesses: words, often ones that can't be the answer,
+// chosen only because they test letters that split the candidates.
+// It's what a person does when the candidates differ by a single
+// letter (e.g. CAVEL, GAVEL, HAZEL, NAVEL): rather than guess them one
+// at a time, play a word containing C, G, Z and N. Once only a couple
+// of candidates remain, or no guess would tell them apart, it falls
+// back to the heuristic strategy.
+
+pub struct Burner;
+
+impl Strategy for Burner {
+    fn pick(&mut self, turn: &Turn, rng: &mut dyn RngCore) -> &'static str {
+        let total = turn.candidates.total();
+
+        if total > 2 {
+            // Count how many candidates contain each letter. A letter
+            // is most useful when it's in half the candidates; a
+            // letter that's in all or none of them tells us nothing.
+
+            let mut counts = [0usize; 26];
+
+            for word in turn.candidates.iter() {
+                let mask = letters(word);
+
+                for (idx, count) in counts.iter_mut().enumerate() {
+                    if mask & (1 << idx) != 0 {
+                        *count += 1
+                    }
+                }
+            }
+
+            let value: Vec<usize> =
+                counts.iter().map(|k| *k.min(&(total - k))).collect();
+
+            // Score each allowed guess by the letters it tests. Ties
+            // go to words that could still be the answer and then to
+            // the word that comes first alphabetically.
+
+            let mut best: Option<(&'static str, (usize, bool))> = None;
+
+            for guess in turn.guesses.iter() {
+                let mask = letters(guess);
+                let score = (0..26)
+                    .filter(|idx| mask & (1 << idx) != 0)
+                    .map(|idx| value[idx])
+                    .sum();
+                let key = (score, turn.candidates.contains(guess));
+
+                match best {
+                    Some((_, best_key)) if best_key >= key => (),
+                    _ => best = Some((guess, key)),
+                }
+            }
+
+            if let Some((word, (score, _))) = best {
+                if score > 0 {
+                    return word;
+                }
+            }
+        }
+        Heuristic.pick(turn, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Words;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_pick() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let guesses =
+            Words::new(&["cavel", "gavel", "hazel", "navel", "cognz", "ravel"]);
+        let candidates = Words::new(&["cavel", "gavel", "hazel", "navel"]);
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[],
+            matrix: None,
+        };
+
+        assert_eq!(Burner.pick(&turn, &mut rng), "cognz");
+
+        // With two candidates left, play one of them.
+
+        let candidates = Words::new(&["gavel", "navel"]);
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[],
+            matrix: None,
+        };
+
+        assert!(candidates.contains(Burner.pick(&turn, &mut rng)));
+    }
+}
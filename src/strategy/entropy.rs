@@ -0,0 +1,151 @@
+use super::{buckets, evaluate, Opener, Strategy, Turn};
+use crate::dictionary::Words;
+use crate::matrix::Matrix;
+use rand::RngCore;
+
+// Returns the expected amount of information, in bits, the hints for
+// `guess` would provide. The remaining vocabulary is split into
+// buckets by the hints each word would produce. The more evenly the
+// words are spread over the buckets, the more a guess tells us. The
+// hints are looked up in `matrix` if one is given.
+
+pub fn entropy(guess: &str, vocab: &Words, matrix: Option<&Matrix>) -> f64 {
+    let total = vocab.total() as f64;
+
+    buckets(guess, vocab, matrix)
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / total;
+
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+// Returns the `n` guesses expected to tell the most, along with the
+// number of bits each is expected to provide. They're ordered the
+// same way `Entropy` chooses between them, best first.
+
+pub fn rank(turn: &Turn, n: usize) -> Vec<(&'static str, f64)> {
+    let mut scores = evaluate(turn, |guess| {
+        (
+            entropy(guess, turn.candidates, turn.matrix),
+            turn.candidates.contains(guess),
+        )
+    });
+
+    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    scores.truncate(n);
+    scores
+        .into_iter()
+        .map(|(word, (bits, _))| (word, bits))
+        .collect()
+}
+
+// Picks guesses that maximize the expected information gained from
+// the hints. Any allowed guess can be used as a probe, even if it has
+// already been ruled out as the answer.
+
+#[derive(Default)]
+pub struct Entropy {
+    opener: Opener<&'static str>,
+}
+
+impl Strategy for Entropy {
+    // Scores every allowed guess against the candidates and returns
+    // the best one. Ties go to words that could still be the answer
+    // and then to the word that comes first alphabetically, so the
+    // choice is always the same for a given set of candidates.
+
+    fn pick(&mut self, turn: &Turn, _rng: &mut dyn RngCore) -> &'static str {
+        if let Some(word) = self.opener.get(turn) {
+            return word;
+        }
+
+        let scores = evaluate(turn, |guess| {
+            (
+                entropy(guess, turn.candidates, turn.matrix),
+                turn.candidates.contains(guess),
+            )
+        });
+        let mut best = None;
+
+        for (guess, score) in scores {
+            match best {
+                Some((_, best_score)) if best_score >= score => (),
+                _ => best = Some((guess, score)),
+            }
+        }
+
+        let word = best
+            .map(|(word, _)| word)
+            .or_else(|| turn.candidates.iter().next())
+            .unwrap();
+
+        self.opener.set(turn, word);
+        word
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hint;
+
+    #[test]
+    fn test_entropy() {
+        let vocab = Words::new(&["aaaaa", "bbbbb", "ccccc", "ddddd"]);
+
+        // A guess that gives different hints for every word yields
+        // two bits. A guess that can't tell them apart yields none.
+
+        assert_eq!(entropy("abcdz", &vocab, None), 2.0);
+        assert_eq!(entropy("zzzzz", &vocab, None), 0.0);
+        assert_eq!(entropy("abzzz", &vocab, None), 1.5);
+    }
+
+    #[test]
+    fn test_rank() {
+        let guesses = Words::new(&["aaaaa", "abcdz", "abzzz", "zzzzz"]);
+        let candidates = Words::new(&["aaaaa", "bbbbb", "ccccc", "ddddd"]);
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[],
+            matrix: None,
+        };
+
+        assert_eq!(rank(&turn, 2), vec![("abcdz", 2.0), ("abzzz", 1.5)]);
+        assert_eq!(rank(&turn, 10).len(), 4);
+    }
+
+    #[test]
+    fn test_pick() {
+        let mut strategy = Entropy::default();
+        let guesses =
+            Words::new(&["aaaaa", "bbbbb", "ccccc", "ddddd", "abcdz", "zzzzz"]);
+        let candidates = Words::new(&["aaaaa", "bbbbb", "ccccc", "ddddd"]);
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[],
+            matrix: None,
+        };
+
+        assert_eq!(strategy.pick(&turn, &mut rand::thread_rng()), "abcdz");
+
+        // With two words left, no guess can do better than one bit,
+        // so one of the candidates is chosen.
+
+        let candidates = Words::new(&["bbbbb", "ccccc"]);
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[("abcdz".to_string(), vec![Hint::Black; 5])],
+            matrix: None,
+        };
+
+        assert_eq!(strategy.pick(&turn, &mut rand::thread_rng()), "bbbbb");
+    }
+}
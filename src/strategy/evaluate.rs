@@ -0,0 +1,64 @@
+use super::Turn;
+use rayon::prelude::*;
+
+// Below this many guess/candidate pairs, a turn is scored on the
+// calling thread. Handing out the work costs more than it saves.
+
+const PARALLEL_WORK: usize = 50_000;
+
+// Scores every allowed guess of a turn. Large turns are split across
+// rayon's threads (see the `--threads` option). The scores come back
+// in the same order as `turn.guesses` however many threads did the
+// work, so a strategy choosing from them makes the same choice every
+// time.
+
+pub fn evaluate<T: Send>(
+    turn: &Turn, score: impl Fn(&'static str) -> T + Sync,
+) -> Vec<(&'static str, T)> {
+    let work = turn.guesses.total() * turn.candidates.total();
+    let guesses: Vec<&'static str> = turn.guesses.iter().collect();
+
+    if work < PARALLEL_WORK {
+        guesses.into_iter().map(|e| (e, score(e))).collect()
+    } else {
+        guesses.into_par_iter().map(|e| (e, score(e))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::{WordLists, Words};
+    use crate::strategy::entropy;
+    use rayon::ThreadPoolBuilder;
+
+    #[test]
+    fn test_evaluate() {
+        let lists = WordLists::new();
+        let candidates: Vec<&str> =
+            lists.vocabulary().iter().take(50).collect();
+        let guesses: Vec<&str> = lists.guesses().iter().step_by(10).collect();
+        let (candidates, guesses) =
+            (Words::new(&candidates), Words::new(&guesses));
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[],
+            matrix: None,
+        };
+
+        assert!(guesses.total() * candidates.total() >= PARALLEL_WORK);
+
+        let run = |threads| {
+            ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| evaluate(&turn, |e| entropy(e, &candidates, None)))
+        };
+        let scores = run(1);
+
+        assert!(scores.iter().map(|e| e.0).eq(guesses.iter()));
+        assert_eq!(scores, run(4));
+    }
+}
@@ -0,0 +1,122 @@
+use super::{Strategy, Turn};
+use crate::{dictionary::Words, feedback};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+// Wraps another strategy so it follows Wordle's hard mode rules. The
+// wrapped strategy only gets to choose from the guesses that reuse
+// every clue revealed so far. The words that could still be the
+// answer always follow the rules, so only the probing strategies are
+// really affected.
+//
+// If `explain` is set, the wrapped strategy also picks from the
+// unrestricted guesses so the report can say when hard mode made a
+// difference. This doubles the work of a turn, so it's only done
+// when someone is going to read the report.
+
+pub struct Hard {
+    inner: Box<dyn Strategy>,
+    explain: bool,
+    changed: Option<&'static str>,
+}
+
+impl Hard {
+    pub fn new(mut inner: Box<dyn Strategy>, explain: bool) -> Hard {
+        inner.hard_mode();
+
+        Hard {
+            inner,
+            explain,
+            changed: None,
+        }
+    }
+}
+
+// Returns the guesses hard mode allows, given the clues so far. They
+// share the index of `turn.guesses`, so the saved opener and the
+// pattern matrix still work with them.
+
+pub fn legal_guesses(turn: &Turn) -> Words {
+    let mut legal = turn.guesses.clone();
+
+    legal.retain_words(|word| {
+        turn.history.iter().all(|(guess, hints)| {
+            feedback::hard_mode_allows(word, guess, hints)
+        })
+    });
+    legal
+}
+
+impl Strategy for Hard {
+    fn pick(&mut self, turn: &Turn, rng: &mut dyn RngCore) -> &'static str {
+        let guesses = legal_guesses(turn);
+        let hard_turn = Turn {
+            guesses: &guesses,
+            ..*turn
+        };
+
+        // Both picks draw from copies of the same generator so any
+        // difference between them is due to the restriction. Taking
+        // the seed from `rng` keeps games reproducible whether or
+        // not the comparison is made.
+
+        let sub_rng = StdRng::seed_from_u64(rng.next_u64());
+
+        self.changed = None;
+
+        if self.explain && guesses.total() < turn.guesses.total() {
+            let free = self.inner.pick(turn, &mut sub_rng.clone());
+
+            if !guesses.contains(free) {
+                self.changed = Some(free)
+            }
+        }
+
+        self.inner.pick(&hard_turn, &mut sub_rng.clone())
+    }
+
+    fn report(&self) -> Option<String> {
+        let note = self
+            .changed
+            .map(|word| format!("hard mode ruled out {}", word.to_uppercase()));
+
+        match (self.inner.report(), note) {
+            (Some(report), Some(note)) => Some(format!("{}; {}", report, note)),
+            (report, note) => report.or(note),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hint;
+
+    #[test]
+    fn test_legal_guesses() {
+        let guesses = Words::new(&["camel", "hazel", "otter", "tatty"]);
+        let candidates = Words::new(&["otter"]);
+        let history = [(
+            "tithe".to_string(),
+            crate::feedback::score("tithe", "otter"),
+        )];
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &history,
+            matrix: None,
+        };
+
+        let legal = legal_guesses(&turn);
+
+        assert_eq!(legal, Words::new(&["otter"]));
+        assert!(legal.shares_index(&guesses));
+
+        let history = [("camel".to_string(), vec![Hint::Black; 5])];
+        let turn = Turn {
+            history: &history,
+            ..turn
+        };
+
+        assert_eq!(legal_guesses(&turn), guesses);
+    }
+}
@@ -0,0 +1,93 @@
+use super::{Strategy, Turn};
+use crate::Set;
+use itertools::Itertools;
+use rand::{Rng, RngCore};
+
+// The original wordlebot strategy. It tries to determine the best
+// words to choose from the set of candidates. First, a subset of the
+// candidates is made consisting of the "best" words. From this set, a
+// random one is chosen. If the "best" set is empty, then we choose a
+// random one from all the candidates.
+
+pub struct Heuristic;
+
+impl Strategy for Heuristic {
+    fn pick(&mut self, turn: &Turn, rng: &mut dyn RngCore) -> &'static str {
+        let remaining_words = turn.candidates.total();
+        let best: Set<&'static str> = turn
+            .candidates
+            .iter()
+            .filter(|word| {
+                word.find(['a', 'e', 'i', 'o', 'u', 'y']).is_some()
+                    && word.find("uo").is_none()
+                    && word.find("ln").is_none()
+                    && word.find("kh").is_none()
+                    && word.find("eu").is_none()
+                    && (remaining_words <= 100
+                        || word
+                            .chars()
+                            .sorted()
+                            .dedup()
+                            .filter(|c| {
+                                *c != 'z'
+                                    && *c != 'q'
+                                    && *c != 'v'
+                                    && *c != 'x'
+                                    && *c != 'j'
+                            })
+                            .count()
+                            == word.len())
+            })
+            .collect();
+
+        if best.is_empty() {
+            let choice = rng.gen_range(0..remaining_words);
+
+            turn.candidates.iter().nth(choice).unwrap()
+        } else {
+            let choice = rng.gen_range(0..best.len());
+
+            best.iter().nth(choice).copied().unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Words;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_pick_words() {
+        const ANSWER: &str = "infer";
+        let mut words = vec![
+            ANSWER, "crwth", "fluor", "pizza", "quiet", "exact", "jelly",
+            "petty", "swoln", "rikha",
+        ];
+
+        // The strictest filter is only applied when more than 100
+        // words remain, so pad the set with words it always rejects.
+
+        for ii in 0..100 {
+            words.push(crate::dictionary::intern(&format!("x{:04}", ii)));
+        }
+
+        let words = Words::new(&words);
+        let turn = Turn {
+            candidates: &words,
+            guesses: &words,
+            history: &[],
+            matrix: None,
+        };
+
+        // Every seed should lead to the only word that passes the
+        // filter.
+
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            assert_eq!(Heuristic.pick(&turn, &mut rng), ANSWER);
+        }
+    }
+}
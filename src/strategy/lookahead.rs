@@ -0,0 +1,322 @@
+use super::{legal_guesses, rank, Opener, Strategy, Turn};
+use crate::dictionary::{CharFreqTable, GreenTable, Words};
+use crate::matrix::{self, Matrix};
+use crate::{feedback, Hint, Map};
+use rand::RngCore;
+
+// Picks the guess expected to solve the puzzle in the fewest guesses.
+// The `width` guesses expected to tell the most (see `rank()`) are
+// tried. The hints each one can get sort the candidates into
+// buckets, and each bucket is played the same way, looking `depth`
+// guesses ahead in all. Past that, the guesses a bucket needs are
+// estimated from its size.
+//
+// The buckets are made by `process_hints()`, so they hold the words
+// a game would really have left after the hints. With a pattern
+// matrix, the hints are looked up and the buckets filtered by it.
+// In hard mode, the guesses tried after the first are the ones the
+// rules allow, given the hints played out so far.
+
+pub struct Lookahead {
+    width: usize,
+    depth: usize,
+    hard: bool,
+    opener: Opener<(&'static str, f64)>,
+    last: Option<(&'static str, f64)>,
+
+    // The tables `process_hints()` uses, along with the words they
+    // were built for. They're kept while the candidates are among
+    // those words, so they're built once a game.
+    tables: Option<(Words, GreenTable, CharFreqTable)>,
+}
+
+impl Lookahead {
+    pub fn new(width: usize, depth: usize) -> Lookahead {
+        Lookahead {
+            width: width.max(1),
+            depth: depth.max(1),
+            hard: false,
+            opener: Opener::default(),
+            last: None,
+            tables: None,
+        }
+    }
+
+    // Returns the expected number of guesses it takes to solve the
+    // turn's candidates, and the guess to play for it.
+
+    fn search(&mut self, turn: &Turn) -> (&'static str, f64) {
+        let fits = self
+            .tables
+            .as_ref()
+            .is_some_and(|(words, _, _)| turn.candidates.is_subset(words));
+
+        if !fits {
+            self.tables = Some((
+                turn.candidates.clone(),
+                GreenTable::for_words(turn.candidates),
+                CharFreqTable::for_words(turn.candidates),
+            ))
+        }
+
+        let (_, gt, ft) = self.tables.as_ref().unwrap();
+
+        Search {
+            guesses: turn.guesses,
+            matrix: turn.matrix,
+            gt,
+            ft,
+            width: self.width,
+            hard: self.hard,
+        }
+        .best(turn.candidates, &mut turn.history.to_vec(), self.depth)
+    }
+}
+
+// Estimates the guesses it takes to solve `n` candidates without
+// looking ahead. Each guess is taken to divide the candidates by
+// about eight.
+
+fn estimate(n: usize) -> f64 {
+    1.0 + (n as f64).log2() / 3.0
+}
+
+struct Search<'a> {
+    guesses: &'a Words,
+    matrix: Option<&'a Matrix>,
+    gt: &'a GreenTable,
+    ft: &'a CharFreqTable,
+    width: usize,
+    hard: bool,
+}
+
+impl Search<'_> {
+    // Returns the expected number of guesses it takes to solve the
+    // candidates after the guesses in `history`, looking `depth`
+    // guesses ahead. With one or two candidates, guessing one of them
+    // can't be beaten.
+
+    fn solve(
+        &self, candidates: &Words, history: &mut Vec<(String, Vec<Hint>)>,
+        depth: usize,
+    ) -> f64 {
+        match candidates.total() {
+            1 => 1.0,
+            2 => 1.5,
+            n if depth == 0 => estimate(n),
+            _ => self.best(candidates, history, depth).1,
+        }
+    }
+
+    // Returns the guess expected to solve the candidates in the
+    // fewest guesses, along with that number. Ties go to the guess
+    // `rank()` puts first.
+
+    fn best(
+        &self, candidates: &Words, history: &mut Vec<(String, Vec<Hint>)>,
+        depth: usize,
+    ) -> (&'static str, f64) {
+        let legal;
+        let mut turn = Turn {
+            candidates,
+            guesses: self.guesses,
+            history,
+            matrix: self.matrix,
+        };
+
+        if self.hard {
+            legal = legal_guesses(&turn);
+            turn.guesses = &legal
+        }
+
+        let mut best: Option<(&'static str, f64)> = None;
+
+        for (guess, _) in rank(&turn, self.width) {
+            let expected = self.expected(guess, candidates, history, depth);
+
+            if best.is_none_or(|(_, least)| expected < least) {
+                best = Some((guess, expected))
+            }
+        }
+        best.unwrap_or_else(|| (candidates.iter().next().unwrap(), f64::MAX))
+    }
+
+    // Returns the expected number of guesses it takes to solve the
+    // candidates if `guess` is played next. Each set of hints is
+    // weighted by the number of candidates that would give it.
+
+    fn expected(
+        &self, guess: &str, candidates: &Words,
+        history: &mut Vec<(String, Vec<Hint>)>, depth: usize,
+    ) -> f64 {
+        let total = candidates.total() as f64;
+        let mut counts: Map<u16, (usize, &str)> = Map::new();
+        let patterns: Box<dyn Iterator<Item = u16>> =
+            match self.matrix.and_then(|e| e.row(guess, candidates)) {
+                Some(row) => Box::new(row),
+                None => Box::new(
+                    candidates.iter().map(|e| feedback::pattern(guess, e)),
+                ),
+            };
+
+        for (pattern, word) in patterns.zip(candidates.iter()) {
+            counts.entry(pattern).or_insert((0, word)).0 += 1
+        }
+
+        let mut sum = 0.0;
+
+        for (count, word) in counts.values().filter(|(_, e)| *e != guess) {
+            let hints = feedback::score(guess, word);
+            let words = matrix::process_hints(
+                self.matrix,
+                candidates.clone(),
+                self.gt,
+                self.ft,
+                guess,
+                &hints,
+            );
+
+            history.push((guess.to_string(), hints));
+            sum +=
+                *count as f64 / total * self.solve(&words, history, depth - 1);
+            history.pop();
+        }
+        1.0 + sum
+    }
+}
+
+impl Strategy for Lookahead {
+    fn pick(&mut self, turn: &Turn, _rng: &mut dyn RngCore) -> &'static str {
+        let result = self.opener.get(turn).unwrap_or_else(|| {
+            let result = if turn.candidates.total() <= 2 {
+                (
+                    turn.candidates.iter().next().unwrap(),
+                    if turn.candidates.total() == 1 {
+                        1.0
+                    } else {
+                        1.5
+                    },
+                )
+            } else {
+                self.search(turn)
+            };
+
+            self.opener.set(turn, result);
+            result
+        });
+
+        self.last = Some(result);
+        result.0
+    }
+
+    fn report(&self) -> Option<String> {
+        self.last.map(|(word, expected)| {
+            format!(
+                "{} is expected to solve it in {:.2} guesses",
+                word.to_uppercase(),
+                expected
+            )
+        })
+    }
+
+    fn hard_mode(&mut self) {
+        self.hard = true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick() {
+        let guesses = Words::new(&[
+            "aaaab", "aaaac", "aaaad", "aaaae", "bcdez", "bcxxx", "zzzzz",
+        ]);
+        let candidates = Words::new(&["aaaab", "aaaac", "aaaad", "aaaae"]);
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[],
+            matrix: None,
+        };
+
+        // BCDEZ tells the four candidates apart, so the answer comes
+        // next. Guessing a candidate could win right away, but the
+        // three it leaves take two more guesses on average.
+
+        let mut strategy = Lookahead::new(7, 2);
+
+        assert_eq!(strategy.pick(&turn, &mut rand::thread_rng()), "bcdez");
+        assert_eq!(
+            strategy.report(),
+            Some("BCDEZ is expected to solve it in 2.00 guesses".to_string())
+        );
+
+        // With a width of one, only the guess `rank()` puts first is
+        // tried.
+
+        let mut strategy = Lookahead::new(1, 2);
+        let first = rank(&turn, 1)[0].0;
+
+        assert_eq!(strategy.pick(&turn, &mut rand::thread_rng()), first);
+    }
+
+    #[test]
+    fn test_expected() {
+        let guesses = Words::new(&["aaaab", "aaaac", "aaaad", "bcdez"]);
+        let candidates = Words::new(&["aaaab", "aaaac", "aaaad"]);
+        let gt = GreenTable::for_words(&candidates);
+        let ft = CharFreqTable::for_words(&candidates);
+        let search = Search {
+            guesses: &guesses,
+            matrix: None,
+            gt: &gt,
+            ft: &ft,
+            width: 4,
+            hard: false,
+        };
+
+        // AAAAB wins a third of the time. Otherwise two candidates
+        // are left, which take 1.5 guesses.
+
+        for expected in [
+            search.expected("aaaab", &candidates, &mut vec![], 1),
+            search.expected("bcdez", &candidates, &mut vec![], 1),
+            search.solve(&candidates, &mut vec![], 2),
+        ] {
+            assert!((expected - 2.0).abs() < 1e-9, "{}", expected)
+        }
+    }
+
+    #[test]
+    fn test_hard() {
+        let guesses =
+            Words::new(&["aaaab", "aaaac", "aaaad", "aaaae", "aaaaf", "cdefz"]);
+        let candidates =
+            Words::new(&["aaaab", "aaaac", "aaaad", "aaaae", "aaaaf"]);
+        let gt = GreenTable::for_words(&candidates);
+        let ft = CharFreqTable::for_words(&candidates);
+        let expected = |hard| {
+            Search {
+                guesses: &guesses,
+                matrix: None,
+                gt: &gt,
+                ft: &ft,
+                width: 6,
+                hard,
+            }
+            .expected("aaaab", &candidates, &mut vec![], 2)
+        };
+
+        // After AAAAB, CDEFZ tells the other four candidates apart.
+        // Hard mode rules it out, so one of them has to be guessed.
+
+        for (expected, least) in [
+            (expected(false), 1.0 + 0.8 * 2.0),
+            (expected(true), 1.0 + 0.8 * (1.0 + 0.75 * estimate(3))),
+        ] {
+            assert!((expected - least).abs() < 1e-9, "{}", expected)
+        }
+    }
+}
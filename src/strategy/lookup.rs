@@ -0,0 +1,100 @@
+use super::{Strategy, Turn};
+use crate::tree::Tree;
+use rand::RngCore;
+use std::sync::Arc;
+
+// Plays the guesses of a solving tree (see `tree::Tree`) instead of
+// working them out. Once a game leaves the tree, because a different
+// word was played or the tree has no branch for the hints, the
+// wrapped strategy picks the guesses.
+
+pub struct Lookup {
+    tree: Arc<Tree>,
+    inner: Box<dyn Strategy>,
+    found: bool,
+}
+
+impl Lookup {
+    pub fn new(tree: Arc<Tree>, inner: Box<dyn Strategy>) -> Lookup {
+        Lookup {
+            tree,
+            inner,
+            found: false,
+        }
+    }
+}
+
+impl Strategy for Lookup {
+    fn pick(&mut self, turn: &Turn, rng: &mut dyn RngCore) -> &'static str {
+        match self.tree.follow(turn.history) {
+            Some(node) if turn.guesses.contains(node.guess) => {
+                self.found = true;
+                node.guess
+            }
+            _ => {
+                self.found = false;
+                self.inner.pick(turn, rng)
+            }
+        }
+    }
+
+    fn report(&self) -> Option<String> {
+        if self.found {
+            Some("from the tree".to_string())
+        } else {
+            match self.inner.report() {
+                Some(report) => Some(format!("off the tree; {}", report)),
+                None => Some("off the tree".to_string()),
+            }
+        }
+    }
+
+    fn hard_mode(&mut self) {
+        self.inner.hard_mode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Words;
+    use crate::feedback;
+
+    #[test]
+    fn test_pick() {
+        let guesses = Words::new(&["camel", "hazel", "otter", "tatty"]);
+        let text = "tatty BGBBB1 hazel GGGGG2\ntatty GGGGG1";
+        let tree = Tree::parse("test", text, &guesses).unwrap();
+        let mut strategy = Lookup::new(
+            Arc::new(tree),
+            crate::strategy::build("random").unwrap(),
+        );
+        let mut rng = rand::thread_rng();
+        let candidates = Words::new(&["camel", "hazel", "tatty"]);
+        let mut pick = |history: &[(String, Vec<crate::Hint>)]| {
+            strategy.pick(
+                &Turn {
+                    candidates: &candidates,
+                    guesses: &guesses,
+                    history,
+                    matrix: None,
+                },
+                &mut rng,
+            )
+        };
+
+        assert_eq!(pick(&[]), "tatty");
+        assert_eq!(
+            pick(&[("tatty".to_string(), feedback::score("tatty", "hazel"))]),
+            "hazel"
+        );
+
+        // Off the tree, the random strategy picks a candidate.
+
+        assert!(candidates.contains(pick(&[(
+            "otter".to_string(),
+            feedback::score("otter", "hazel")
+        )])));
+        assert_eq!(strategy.report(), Some("off the tree".to_string()));
+    }
+}
@@ -0,0 +1,118 @@
+use super::{buckets, evaluate, Opener, Strategy, Turn};
+use rand::RngCore;
+
+// Picks the guess whose worst-case hints leave the fewest candidates.
+// This doesn't produce the best average, but it bounds how badly a
+// game can go, which is what matters when trying to guarantee a
+// solution in a given number of guesses.
+
+#[derive(Default)]
+pub struct Minimax {
+    opener: Opener<(&'static str, usize)>,
+    last: Option<(&'static str, usize)>,
+}
+
+impl Minimax {
+    // Returns the guess with the smallest worst-case bucket, along
+    // with the size of that bucket. Ties go to words that could still
+    // be the answer and then to the word that comes first
+    // alphabetically.
+
+    fn search(turn: &Turn) -> (&'static str, usize) {
+        let scores = evaluate(turn, |guess| {
+            let worst = buckets(guess, turn.candidates, turn.matrix)
+                .iter()
+                .copied()
+                .max()
+                .unwrap_or(0);
+
+            (worst, turn.candidates.contains(guess))
+        });
+        let mut best: Option<(&'static str, usize, bool)> = None;
+
+        for (guess, (worst, candidate)) in scores {
+            match best {
+                Some((_, best_worst, best_candidate))
+                    if (best_worst, !best_candidate) <= (worst, !candidate) => {
+                }
+                _ => best = Some((guess, worst, candidate)),
+            }
+        }
+
+        match best {
+            Some((word, worst, _)) => (word, worst),
+            None => (turn.candidates.iter().next().unwrap(), 1),
+        }
+    }
+}
+
+impl Strategy for Minimax {
+    fn pick(&mut self, turn: &Turn, _rng: &mut dyn RngCore) -> &'static str {
+        let result = self.opener.get(turn).unwrap_or_else(|| {
+            let result = Minimax::search(turn);
+
+            self.opener.set(turn, result);
+            result
+        });
+
+        self.last = Some(result);
+        result.0
+    }
+
+    fn report(&self) -> Option<String> {
+        self.last.map(|(word, worst)| {
+            format!(
+                "{} leaves at most {} word{}",
+                word.to_uppercase(),
+                worst,
+                if worst == 1 { "" } else { "s" }
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Words;
+
+    #[test]
+    fn test_pick() {
+        let guesses =
+            Words::new(&["aaaab", "aaaac", "aaaad", "aaaae", "bcdez", "zzzzz"]);
+        let candidates = Words::new(&["aaaab", "aaaac", "aaaad", "aaaae"]);
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[],
+            matrix: None,
+        };
+        let mut strategy = Minimax::default();
+
+        // BCDEZ separates all four candidates. Any candidate would
+        // leave three words in the same bucket when it's wrong.
+
+        assert_eq!(strategy.pick(&turn, &mut rand::thread_rng()), "bcdez");
+        assert_eq!(
+            strategy.report(),
+            Some("BCDEZ leaves at most 1 word".to_string())
+        );
+
+        // When no guess can separate the words, a candidate wins the
+        // tie.
+
+        let candidates = Words::new(&["aaaab", "aaaac"]);
+        let turn = Turn {
+            candidates: &candidates,
+            guesses: &guesses,
+            history: &[("bcdez".to_string(), vec![crate::Hint::Black; 5])],
+            matrix: None,
+        };
+
+        assert_eq!(strategy.pick(&turn, &mut rand::thread_rng()), "aaaab");
+        assert_eq!(
+            strategy.report(),
+            Some("AAAAB leaves at most 1 word".to_string())
+        );
+    }
+}
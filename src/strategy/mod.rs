@@ -0,0 +1,322 @@
+use crate::dictionary::Words;
+use crate::matrix::Matrix;
+use crate::{feedback, Hint};
+use clap::PossibleValue;
+use rand::RngCore;
+
+mod burner;
+mod entropy;
+mod evaluate;
+mod hard;
+mod heuristic;
+mod lookahead;
+mod lookup;
+mod minimax;
+mod random;
+
+pub use entropy::{entropy, rank};
+pub use evaluate::evaluate;
+pub use hard::{legal_guesses, Hard};
+pub use lookup::Lookup;
+
+// Everything a strategy gets to look at when picking a guess.
+// `candidates` holds the words that could still be the answer, given
+// the hints so far. `guesses` holds every word the strategy is
+// allowed to play; a strategy may probe with a word that has already
+// been ruled out. `history` holds the guesses made so far along with
+// the hints they received. `matrix`, if the session loaded one, holds
+// the hints of every guess against every answer so strategies can
+// look them up.
+
+pub struct Turn<'a> {
+    pub candidates: &'a Words,
+    pub guesses: &'a Words,
+    pub history: &'a [(String, Vec<Hint>)],
+    pub matrix: Option<&'a Matrix>,
+}
+
+// A policy for choosing guesses. Strategies are built once per
+// session, so they may keep state between calls (e.g. to cache an
+// expensive result.)
+
+pub trait Strategy {
+    // Returns the next word to guess. `turn.candidates` will never be
+    // empty. Strategies that make random choices must draw from
+    // `rng` so a game can be replayed by reusing its seed.
+
+    fn pick(&mut self, turn: &Turn, rng: &mut dyn RngCore) -> &'static str;
+
+    // Returns a short description of how the last guess was chosen.
+    // It's shown in verbose mode.
+
+    fn report(&self) -> Option<String> {
+        None
+    }
+
+    // Tells the strategy that its games follow hard mode rules. `Hard`
+    // only passes it the guesses the rules allow on each turn, so a
+    // strategy needs this only if it plays out later turns itself.
+
+    fn hard_mode(&mut self) {}
+}
+
+// Sorts `words` into buckets by the hints `guess` would get if each
+// was the answer. The returned vector holds the size of each bucket,
+// indexed by `feedback::pattern()`. The hints are looked up in the
+// matrix if it can be used.
+
+fn buckets(guess: &str, words: &Words, matrix: Option<&Matrix>) -> Vec<usize> {
+    let mut buckets = vec![0usize; feedback::patterns(guess.len())];
+
+    match matrix.and_then(|e| e.row(guess, words)) {
+        Some(row) => row.for_each(|pattern| buckets[pattern as usize] += 1),
+        None => {
+            for word in words.iter() {
+                buckets[feedback::pattern(guess, word) as usize] += 1
+            }
+        }
+    }
+    buckets
+}
+
+// Remembers the outcome of a strategy's first guess. For the
+// strategies that search every allowed guess, it's the most expensive
+// search of the game and its result only changes if the word lists
+// do, so the lists are kept to check against. Comparing sets that
+// share an index only compares their bits.
+
+struct Opener<T>(Option<(Words, Words, T)>);
+
+impl<T> Default for Opener<T> {
+    fn default() -> Self {
+        Opener(None)
+    }
+}
+
+impl<T: Copy> Opener<T> {
+    // Returns the saved outcome if this is the first turn of a game
+    // using the same word lists.
+
+    fn get(&self, turn: &Turn) -> Option<T> {
+        match &self.0 {
+            Some((candidates, guesses, value))
+                if turn.history.is_empty()
+                    && candidates == turn.candidates
+                    && guesses == turn.guesses =>
+            {
+                Some(*value)
+            }
+            _ => None,
+        }
+    }
+
+    // Saves the outcome if this is the first turn.
+
+    fn set(&mut self, turn: &Turn, value: T) {
+        if turn.history.is_empty() {
+            self.0 =
+                Some((turn.candidates.clone(), turn.guesses.clone(), value))
+        }
+    }
+}
+
+// The settings of the strategies that have any. They're given on the
+// command line.
+
+#[derive(Debug)]
+pub struct Settings {
+    // The number of guesses the lookahead strategy tries at each step
+    // and how many guesses deep it looks.
+    pub lookahead_width: usize,
+    pub lookahead_depth: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            lookahead_width: 10,
+            lookahead_depth: 2,
+        }
+    }
+}
+
+// Describes a strategy that can be chosen from the command line.
+
+struct Entry {
+    name: &'static str,
+    help: &'static str,
+    build: fn(&Settings) -> Box<dyn Strategy>,
+}
+
+// The registry of strategies. To add a strategy, implement the
+// `Strategy` trait in a new module and add an entry here. The first
+// entry is the default.
+
+const REGISTRY: &[Entry] = &[
+    Entry {
+        name: "heuristic",
+        help: "random word without uncommon letters or letter pairs",
+        build: |_| Box::new(heuristic::Heuristic),
+    },
+    Entry {
+        name: "entropy",
+        help: "word whose hints are expected to tell the most",
+        build: |_| Box::new(entropy::Entropy::default()),
+    },
+    Entry {
+        name: "minimax",
+        help: "word that leaves the fewest candidates in the worst case",
+        build: |_| Box::new(minimax::Minimax::default()),
+    },
+    Entry {
+        name: "lookahead",
+        help: "word expected to solve it in the fewest guesses",
+        build: |settings| {
+            Box::new(lookahead::Lookahead::new(
+                settings.lookahead_width,
+                settings.lookahead_depth,
+            ))
+        },
+    },
+    Entry {
+        name: "burner",
+        help: "word testing the most useful letters, even if it can't win",
+        build: |_| Box::new(burner::Burner),
+    },
+    Entry {
+        name: "random",
+        help: "any word that could be the answer",
+        build: |_| Box::new(random::Random),
+    },
+];
+
+pub const DEFAULT: &str = REGISTRY[0].name;
+
+// Returns the registered strategies in the form clap uses to
+// validate and document the `--strategy` option.
+
+pub fn possible_values() -> Vec<PossibleValue<'static>> {
+    REGISTRY
+        .iter()
+        .map(|e| PossibleValue::new(e.name).help(e.help))
+        .collect()
+}
+
+// Builds the strategy with the given name, using the default
+// settings.
+
+pub fn build(name: &str) -> Option<Box<dyn Strategy>> {
+    build_with(name, &Settings::default())
+}
+
+pub fn build_with(
+    name: &str, settings: &Settings,
+) -> Option<Box<dyn Strategy>> {
+    REGISTRY
+        .iter()
+        .find(|e| e.name == name)
+        .map(|e| (e.build)(settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Every registered strategy should return one of the allowed
+    // guesses and, when there's only one candidate left, the
+    // candidate.
+
+    #[test]
+    fn test_registry() {
+        let guesses = Words::new(&["camel", "otter", "tatty", "hazel"]);
+        let candidates = Words::new(&["camel", "hazel"]);
+        let last = Words::new(&["otter"]);
+
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for entry in REGISTRY {
+            let mut strategy = build(entry.name).unwrap();
+            let turn = Turn {
+                candidates: &candidates,
+                guesses: &guesses,
+                history: &[],
+                matrix: None,
+            };
+
+            assert!(
+                guesses.contains(strategy.pick(&turn, &mut rng)),
+                "{}",
+                entry.name
+            );
+
+            let turn = Turn {
+                candidates: &last,
+                guesses: &guesses,
+                history: &[],
+                matrix: None,
+            };
+
+            assert_eq!(
+                strategy.pick(&turn, &mut rng),
+                "otter",
+                "{}",
+                entry.name
+            );
+        }
+        assert!(build("bogus").is_none());
+    }
+
+    // Replaying a game with the same seed should produce the same
+    // guesses.
+
+    #[test]
+    fn test_seed() {
+        let words = crate::dictionary::WordLists::new().vocabulary();
+        let turn = Turn {
+            candidates: &words,
+            guesses: &words,
+            history: &[],
+            matrix: None,
+        };
+
+        for name in ["heuristic", "random"] {
+            let mut strategy = build(name).unwrap();
+            let mut picks = |seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+
+                (0..10)
+                    .map(|_| strategy.pick(&turn, &mut rng))
+                    .collect::<Vec<_>>()
+            };
+            let first = picks(1234);
+
+            assert_eq!(first, picks(1234), "{}", name);
+            assert_ne!(first, picks(4321), "{}", name);
+        }
+    }
+
+    // The saved opener is only used with the lists it was found for,
+    // even if other lists are the same size.
+
+    #[test]
+    fn test_opener() {
+        let guesses = Words::new(&["camel", "otter", "tatty", "hazel"]);
+        let first = Words::new(&["camel", "hazel"]);
+        let same = Words::new(&["hazel", "camel"]);
+        let second = Words::new(&["otter", "tatty"]);
+        let turn = |candidates| Turn {
+            candidates,
+            guesses: &guesses,
+            history: &[],
+            matrix: None,
+        };
+        let mut opener = Opener::default();
+
+        opener.set(&turn(&first), "camel");
+
+        assert_eq!(opener.get(&turn(&first)), Some("camel"));
+        assert_eq!(opener.get(&turn(&same)), Some("camel"));
+        assert_eq!(opener.get(&turn(&second)), None);
+    }
+}
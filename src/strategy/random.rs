@@ -0,0 +1,15 @@
+use super::{Strategy, Turn};
+use rand::{Rng, RngCore};
+
+// Picks any word that could still be the answer. This is mostly
+// useful as a baseline when benchmarking other strategies.
+
+pub struct Random;
+
+impl Strategy for Random {
+    fn pick(&mut self, turn: &Turn, rng: &mut dyn RngCore) -> &'static str {
+        let choice = rng.gen_range(0..turn.candidates.total());
+
+        turn.candidates.iter().nth(choice).unwrap()
+    }
+}
@@ -0,0 +1,640 @@
+use crate::dictionary::Words;
+use crate::matrix::Matrix;
+use crate::strategy::{self, Strategy, Turn};
+use crate::{feedback, to_hint_string, Hint, Map};
+use rand::RngCore;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+// A solving tree says what to guess in every game that starts with
+// its opener. Each node holds a guess and, for each set of hints the
+// guess can get (other than all greens), the node to play next.
+//
+// Trees are saved in the text format other Wordle solvers use. Each
+// line follows the game against one answer: every guess followed by
+// its hints and the number of the turn, e.g.
+//
+//     salet BBBBB1 courd BBYBB2 bundy GGGGG3
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Tree {
+    pub guess: &'static str,
+
+    // The next node for each set of hints, keyed by the hints in the
+    // form `to_hint_string()` returns.
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub branches: Map<String, Tree>,
+}
+
+impl Tree {
+    fn leaf(guess: &'static str) -> Tree {
+        Tree {
+            guess,
+            branches: Map::new(),
+        }
+    }
+
+    // Builds the tree that opens with `opener` and solves `answers`
+    // by playing the guess `strategy` picks at each node. Answers it
+    // doesn't solve in `max_guesses` are left out of the tree. An
+    // error is returned if the strategy plays a guess that can't
+    // narrow down the answers left. The hints are looked up in
+    // `matrix` if it can be used, by the strategy too.
+
+    pub fn greedy(
+        opener: &'static str, answers: &Words, guesses: &Words,
+        matrix: Option<&Matrix>, max_guesses: usize,
+        strategy: &mut dyn Strategy, rng: &mut dyn RngCore,
+    ) -> Result<Tree, String> {
+        Greedy {
+            guesses,
+            matrix,
+            strategy,
+            rng,
+        }
+        .play(opener, answers, &mut vec![], max_guesses)
+    }
+
+    // Builds the tree that opens with `opener` and solves `answers` in
+    // the fewest guesses, trying the `width` most informative guesses
+    // at each node. If `hard` is set, the tree follows hard mode
+    // rules. `None` is returned if no such tree solves every answer in
+    // `max_guesses`. The hints are looked up in `matrix` if it can be
+    // used.
+
+    pub fn exhaustive(
+        opener: &'static str, answers: &Words, guesses: &Words,
+        matrix: Option<&Matrix>, width: usize, max_guesses: usize, hard: bool,
+    ) -> Option<Tree> {
+        Search {
+            guesses,
+            matrix,
+            width,
+            hard,
+        }
+        .play(opener, answers, &mut vec![], max_guesses, usize::MAX)
+        .map(|(_, tree)| tree)
+    }
+
+    // Returns the node to play after the guesses in `history`, or
+    // `None` if the game has left the tree.
+
+    pub fn follow(&self, history: &[(String, Vec<Hint>)]) -> Option<&Tree> {
+        history.iter().try_fold(self, |node, (guess, hints)| {
+            if node.guess == *guess {
+                node.branches.get(&to_hint_string(hints))
+            } else {
+                None
+            }
+        })
+    }
+
+    // Returns the guesses the tree plays against `answer`, along with
+    // their hints, or `None` if the tree doesn't solve it.
+
+    pub fn solve(
+        &self, answer: &str,
+    ) -> Option<Vec<(&'static str, Vec<Hint>)>> {
+        let mut node = self;
+        let mut path = vec![];
+
+        loop {
+            let hints = feedback::score(node.guess, answer);
+            let next = node.branches.get(&to_hint_string(&hints));
+            let solved = hints.iter().all(|e| *e == Hint::Green);
+
+            path.push((node.guess, hints));
+
+            if solved {
+                return Some(path);
+            }
+            node = next?
+        }
+    }
+
+    // Writes the tree in the text format, a line for each answer it
+    // solves.
+
+    pub fn write_text(
+        &self, answers: &Words, out: &mut impl Write,
+    ) -> io::Result<()> {
+        for path in answers.iter().filter_map(|e| self.solve(e)) {
+            let line: Vec<String> = path
+                .iter()
+                .enumerate()
+                .map(|(turn, (guess, hints))| {
+                    format!("{} {}{}", guess, to_hint_string(hints), turn + 1)
+                })
+                .collect();
+
+            writeln!(out, "{}", line.join(" "))?
+        }
+        Ok(())
+    }
+
+    // Parses a tree in the text format. The lines can come in any
+    // order but they have to agree on the guess played at each node.
+    // Every guess has to be in `guesses`. Errors start with `name`
+    // and the number of the line.
+
+    pub fn parse(
+        name: &str, text: &str, guesses: &Words,
+    ) -> Result<Tree, String> {
+        let mut root: Option<Tree> = None;
+
+        for (idx, line) in text.lines().enumerate() {
+            let error = |e: String| format!("{}:{}: {}", name, idx + 1, e);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            if fields.is_empty() {
+                continue;
+            }
+            if !fields.len().is_multiple_of(2) {
+                return Err(error("each guess needs its hints".to_string()));
+            }
+
+            let path = fields
+                .chunks(2)
+                .enumerate()
+                .map(|(turn, pair)| parse_move(pair, turn, guesses))
+                .collect::<Result<Vec<_>, String>>()
+                .map_err(error)?;
+
+            for (turn, (_, hints)) in path.iter().enumerate() {
+                let solved = hints.chars().all(|e| e == 'G');
+
+                if solved != (turn == path.len() - 1) {
+                    return Err(error(
+                        "the last guess, and only that one, has to be solved"
+                            .to_string(),
+                    ));
+                }
+            }
+
+            root.get_or_insert_with(|| Tree::leaf(path[0].0))
+                .insert(&path)
+                .map_err(error)?
+        }
+        root.ok_or_else(|| format!("{}: the tree is empty", name))
+    }
+
+    // Reads a tree saved in the text format.
+
+    pub fn read(path: &Path, guesses: &Words) -> io::Result<Tree> {
+        let name = path.display().to_string();
+        let text = fs::read_to_string(path).map_err(|e| {
+            io::Error::new(e.kind(), format!("{}: {}", name, e))
+        })?;
+
+        Tree::parse(&name, &text, guesses)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // Adds the guesses of a line to the tree. The first one is played
+    // at this node.
+
+    fn insert(
+        &mut self, path: &[(&'static str, String)],
+    ) -> Result<(), String> {
+        let (guess, hints) = &path[0];
+
+        if self.guess != *guess {
+            return Err(format!(
+                "{} is played where the tree plays {}",
+                guess.to_uppercase(),
+                self.guess.to_uppercase()
+            ));
+        }
+
+        match path.get(1) {
+            Some((next, _)) => self
+                .branches
+                .entry(hints.clone())
+                .or_insert_with(|| Tree::leaf(next))
+                .insert(&path[1..]),
+            None => Ok(()),
+        }
+    }
+}
+
+// Parses a guess and its hints, e.g. "salet" and "BBYBB2", played on
+// turn `turn` (counting from 0.) The hints are returned in the form
+// `to_hint_string()` returns.
+
+fn parse_move(
+    pair: &[&str], turn: usize, guesses: &Words,
+) -> Result<(&'static str, String), String> {
+    let word = pair[0].to_lowercase();
+    let guess = guesses.find(&word).ok_or_else(|| {
+        format!("{} isn't an allowed guess", word.to_uppercase())
+    })?;
+
+    let error = || format!("\"{}\" isn't hints for {}", pair[1], word);
+    // Split after a hint for each letter, on a character boundary
+    // since the text can hold anything.
+
+    let split = pair[1]
+        .char_indices()
+        .nth(word.len())
+        .map_or(pair[1].len(), |(idx, _)| idx);
+    let (hints, number) = pair[1].split_at(split);
+    let hints: Vec<Hint> = hints
+        .chars()
+        .map(Hint::try_from)
+        .collect::<Result<_, ()>>()
+        .map_err(|_| error())?;
+
+    if hints.len() != word.len() || number.parse() != Ok(turn + 1) {
+        return Err(error());
+    }
+    Ok((guess, to_hint_string(&hints)))
+}
+
+// Converts hints from the form `to_hint_string()` returns.
+
+fn from_hint_string(hints: &str) -> Vec<Hint> {
+    hints.chars().map(|e| Hint::try_from(e).unwrap()).collect()
+}
+
+// Sorts `candidates` into groups by the hints `guess` gets against
+// each, keyed the way `Tree` keys its branches. The answer the guess
+// solves, if it's a candidate, isn't in any group.
+
+fn partition(
+    guess: &str, candidates: &Words, matrix: Option<&Matrix>,
+) -> Map<String, Words> {
+    let patterns: Vec<u16> = match matrix.and_then(|e| e.row(guess, candidates))
+    {
+        Some(row) => row.collect(),
+        None => candidates
+            .iter()
+            .map(|e| feedback::pattern(guess, e))
+            .collect(),
+    };
+    let solved = feedback::encode(&vec![Hint::Green; guess.len()]);
+    let mut groups: Map<u16, (&str, Vec<usize>)> = Map::new();
+
+    for ((pos, word), pattern) in
+        candidates.positions().zip(candidates.iter()).zip(patterns)
+    {
+        if pattern != solved {
+            groups.entry(pattern).or_insert((word, vec![])).1.push(pos)
+        }
+    }
+
+    // Each group is keyed by the hints of one of its words.
+
+    groups
+        .into_values()
+        .map(|(word, positions)| {
+            let mut words = candidates.clone();
+
+            words.retain(|pos| positions.binary_search(&pos).is_ok());
+            (to_hint_string(&feedback::score(guess, word)), words)
+        })
+        .collect()
+}
+
+// Returns `true` if playing `guess` can't tell any of the candidates
+// apart or solve one of them. A tree would never get past it.
+
+fn is_useless(
+    guess: &str, candidates: &Words, groups: &Map<String, Words>,
+) -> bool {
+    !candidates.contains(guess) && groups.len() < 2
+}
+
+// Builds trees by playing the guess `strategy` picks at each node.
+
+struct Greedy<'a> {
+    guesses: &'a Words,
+    matrix: Option<&'a Matrix>,
+    strategy: &'a mut dyn Strategy,
+    rng: &'a mut dyn RngCore,
+}
+
+impl Greedy<'_> {
+    // Returns the tree after playing `guess` with `left` guesses left,
+    // counting this one. Once none are left after it, the candidates
+    // it doesn't solve get no branches.
+
+    fn play(
+        &mut self, guess: &'static str, candidates: &Words,
+        history: &mut Vec<(String, Vec<Hint>)>, left: usize,
+    ) -> Result<Tree, String> {
+        let groups = partition(guess, candidates, self.matrix);
+
+        if is_useless(guess, candidates, &groups) {
+            return Err(format!(
+                "the strategy played {}, which doesn't narrow down {} words",
+                guess.to_uppercase(),
+                candidates.total()
+            ));
+        }
+
+        let mut tree = Tree::leaf(guess);
+
+        if left <= 1 {
+            return Ok(tree);
+        }
+
+        for (hints, words) in groups {
+            history.push((guess.to_string(), from_hint_string(&hints)));
+
+            let next = self.strategy.pick(
+                &Turn {
+                    candidates: &words,
+                    guesses: self.guesses,
+                    history,
+                    matrix: self.matrix,
+                },
+                self.rng,
+            );
+            let subtree = self.play(next, &words, history, left - 1);
+
+            history.pop();
+            tree.branches.insert(hints, subtree?);
+        }
+        Ok(tree)
+    }
+}
+
+// The fewest guesses it can take, in total, to solve `n` answers: one
+// of them is guessed right away and each of the others needs at least
+// one more guess.
+
+fn lower_bound(n: usize) -> usize {
+    2 * n - 1
+}
+
+// Searches for the tree that takes the fewest guesses, in total, to
+// solve every answer. Only the `width` guesses expected to tell the
+// most are tried at each node, so the tree is the best among those.
+// Branches that can't beat the best tree found so far are dropped
+// without being searched.
+
+struct Search<'a> {
+    guesses: &'a Words,
+    matrix: Option<&'a Matrix>,
+    width: usize,
+    hard: bool,
+}
+
+impl Search<'_> {
+    // Returns the tree after playing `guess`, along with the number
+    // of guesses it takes to solve every candidate. `None` is
+    // returned if some candidate would take more than `left` guesses
+    // or all of them would take `budget` or more.
+
+    fn play(
+        &self, guess: &'static str, candidates: &Words,
+        history: &mut Vec<(String, Vec<Hint>)>, left: usize, budget: usize,
+    ) -> Option<(usize, Tree)> {
+        let groups = partition(guess, candidates, self.matrix);
+
+        if left == 0 || is_useless(guess, candidates, &groups) {
+            return None;
+        }
+
+        // Every candidate takes this guess. Until a group is searched,
+        // it's counted at its lower bound.
+
+        let mut total = candidates.total();
+        let mut bound: usize =
+            groups.values().map(|e| lower_bound(e.total())).sum();
+        let mut tree = Tree::leaf(guess);
+
+        for (hints, words) in groups {
+            if total + bound >= budget {
+                return None;
+            }
+            bound -= lower_bound(words.total());
+            history.push((guess.to_string(), from_hint_string(&hints)));
+
+            let best =
+                self.best(&words, history, left - 1, budget - total - bound);
+
+            history.pop();
+
+            let (guesses, subtree) = best?;
+
+            total += guesses;
+            tree.branches.insert(hints, subtree);
+        }
+        Some((total, tree))
+    }
+
+    // Returns the best tree for the candidates, like `play()` does.
+
+    fn best(
+        &self, candidates: &Words, history: &mut Vec<(String, Vec<Hint>)>,
+        left: usize, mut budget: usize,
+    ) -> Option<(usize, Tree)> {
+        let n = candidates.total();
+
+        if left == 0 || lower_bound(n) >= budget {
+            return None;
+        }
+
+        // With one or two candidates left, guessing one of them can't
+        // be beaten.
+
+        if n <= 2 {
+            let guess = candidates.iter().next().unwrap();
+
+            return self.play(guess, candidates, history, left, budget);
+        }
+
+        let legal;
+        let mut turn = Turn {
+            candidates,
+            guesses: self.guesses,
+            history,
+            matrix: self.matrix,
+        };
+
+        if self.hard {
+            legal = strategy::legal_guesses(&turn);
+            turn.guesses = &legal
+        }
+
+        let mut best = None;
+
+        for (guess, _) in strategy::rank(&turn, self.width) {
+            if let Some((total, tree)) =
+                self.play(guess, candidates, history, left, budget)
+            {
+                budget = total;
+                best = Some((total, tree));
+
+                if total == lower_bound(n) {
+                    break;
+                }
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::WordLists;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn lists() -> WordLists {
+        WordLists::with_lists(
+            vec![
+                "camel", "hazel", "otter", "tatty", "geese", "eerie", "cater",
+                "water", "later", "hater",
+            ],
+            vec!["aahed", "teeth", "zymic", "chowl"],
+        )
+    }
+
+    // Every answer should be solved by the tree, and in the same
+    // guesses the text format lists.
+
+    fn check(tree: &Tree, lists: &WordLists) {
+        let answers = lists.vocabulary();
+        let mut text = vec![];
+
+        for answer in answers.iter() {
+            let path = tree.solve(answer).unwrap();
+
+            assert_eq!(path.last().unwrap().0, answer);
+        }
+
+        tree.write_text(&answers, &mut text).unwrap();
+        assert_eq!(
+            &Tree::parse(
+                "test",
+                &String::from_utf8(text).unwrap(),
+                &lists.guesses()
+            )
+            .unwrap(),
+            tree
+        );
+    }
+
+    fn total(tree: &Tree, answers: &Words) -> usize {
+        answers.iter().map(|e| tree.solve(e).unwrap().len()).sum()
+    }
+
+    #[test]
+    fn test_build() {
+        let lists = lists();
+        let answers = lists.vocabulary();
+        let guesses = lists.guesses();
+        let matrix = Matrix::new(&lists);
+        let build = |matrix, max_guesses| {
+            Tree::greedy(
+                "teeth",
+                &answers,
+                &guesses,
+                matrix,
+                max_guesses,
+                strategy::build("entropy").unwrap().as_mut(),
+                &mut StdRng::seed_from_u64(0),
+            )
+            .unwrap()
+        };
+        let greedy = build(None, 6);
+
+        check(&greedy, &lists);
+
+        // With two guesses, only the answers the second guess solves
+        // are in the tree.
+
+        let short = build(None, 2);
+
+        assert!(answers.iter().any(|e| short.solve(e).is_none()));
+        assert!(answers
+            .iter()
+            .filter_map(|e| short.solve(e))
+            .all(|e| e.len() <= 2));
+
+        let search = Search {
+            guesses: &guesses,
+            matrix: None,
+            width: 5,
+            hard: false,
+        };
+        let (guesses, best) = search
+            .play("teeth", &answers, &mut vec![], 6, usize::MAX)
+            .unwrap();
+
+        check(&best, &lists);
+        assert_eq!(guesses, total(&best, &answers));
+        assert!(guesses <= total(&greedy, &answers));
+
+        // Every answer can't be solved in two guesses.
+
+        assert!(search
+            .play("teeth", &answers, &mut vec![], 2, usize::MAX)
+            .is_none());
+
+        // Looking the hints up in the matrix builds the same trees.
+
+        assert_eq!(build(Some(&matrix), 6), greedy);
+        assert_eq!(
+            Tree::exhaustive(
+                "teeth",
+                &answers,
+                &lists.guesses(),
+                Some(&matrix),
+                5,
+                6,
+                false
+            ),
+            Some(best)
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let guesses = Words::new(&["camel", "hazel", "otter"]);
+        let text = "\
+            camel BBBBB1 otter GGGGG2\n\
+            \n\
+            camel GGGGG1\n\
+            camel BYBGG1 hazel GGGGG2\n";
+        let tree = Tree::parse("test", text, &guesses).unwrap();
+
+        assert_eq!(tree.guess, "camel");
+        assert_eq!(tree.branches.len(), 2);
+        assert_eq!(tree.branches["BBBBB"], Tree::leaf("otter"));
+        assert_eq!(
+            tree.follow(&[("camel".to_string(), from_hint_string("BYBGG"))]),
+            Some(&Tree::leaf("hazel"))
+        );
+        assert_eq!(
+            tree.follow(&[("otter".to_string(), from_hint_string("BYBGG"))]),
+            None
+        );
+
+        for bad in [
+            "camel BBBBB1 hazel GGGGG2\ncamel BBBBB1 otter GGGGG2",
+            "camel BBBBB1 otter GGGGG3",
+            "camel BBBBB1 otter",
+            "camel BBBBB1 otter BBBBB2",
+            "camel GGGGG1 otter GGGGG2",
+            "camel BXBBB1 otter GGGGG2",
+            "camel 🟩🟩🟩🟩🟩1",
+            "camel BBBB🟩1",
+            "tatty GGGGG1",
+            "",
+        ] {
+            assert!(Tree::parse("test", bad, &guesses).is_err(), "{}", bad)
+        }
+
+        // Errors say where the bad line is.
+
+        assert_eq!(
+            Tree::parse("t.txt", "\ncamel 🟩🟩🟩🟩🟩1", &guesses),
+            Err("t.txt:2: \"🟩🟩🟩🟩🟩1\" isn't hints for camel".to_string())
+        );
+    }
+}